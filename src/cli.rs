@@ -0,0 +1,160 @@
+use crate::logic::{LogicManager, GameStatus, Color};
+use crate::logic::piece::{PieceTypes, parse_square, square_name};
+use crate::logic::pgn::Pgn;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Enter a move in algebraic (Nf3, exd5, O-O, e8=Q) or coordinate (g1f3, e7e8q) notation, or one of:
+  moves <square>  list the legal moves of the piece on <square>
+  undo / redo     take back or replay a move
+  fen             print the current position in FEN
+  pgn             print the game so far in PGN
+  draw            claim a draw by threefold repetition or the fifty move rule
+  resign          resign the game for the side to move
+  help            show this message
+  quit            leave the game";
+
+//plays a game in the terminal, reading moves and commands from stdin
+pub fn run() {
+    let mut lm = LogicManager::new();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    println!("{}\n", HELP);
+    print_board(&lm);
+
+    loop {
+        print!("{} to move> ", color_name(lm.side_to_move()));
+        let _ = io::stdout().flush();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some(command) => command,
+            None => continue,
+        };
+
+        match command {
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => break,
+            "fen" => println!("{}", lm.to_fen()),
+            "pgn" => print!("{}", Pgn::from_game(&lm)),
+            "undo" | "redo" => {
+                let changed = if command == "undo" { lm.undo() } else { lm.redo() };
+                if changed {
+                    print_board(&lm);
+                } else {
+                    println!("Nothing to {}", command);
+                }
+            }
+            "moves" => match words.next().and_then(parse_square) {
+                Some(pos) => print_moves(&mut lm, pos),
+                None => println!("Usage: moves <square>, e.g. moves e2"),
+            },
+            "draw" => {
+                if lm.claim_draw().is_some() {
+                    report_status(&lm);
+                } else {
+                    println!("There is no draw to claim");
+                }
+            }
+            "resign" => {
+                let turn = lm.side_to_move();
+                println!("{} resigns, {} wins", color_name(turn), color_name(turn.opposite()).to_lowercase());
+                break;
+            }
+            _ => {
+                if lm.status().is_over() {
+                    println!("The game is over, use undo to take back a move or quit to leave");
+                    continue;
+                }
+                let outcome = lm.parse_san(command).map_err(|e| e.to_string())
+                    .and_then(|mv| lm.make_move(mv).map_err(|e| e.to_string()));
+                match outcome {
+                    Ok(outcome) => {
+                        print_board(&lm);
+                        println!("{} played {}", color_name(lm.side_to_move().opposite()), outcome.san);
+                        report_status(&lm);
+                    }
+                    Err(e) => println!("{}, type help for the list of commands", e),
+                }
+            }
+        }
+    }
+}
+
+fn print_moves(lm: &mut LogicManager, pos: (i8, i8)) {
+    if lm.get_piece_color(pos) != Some(lm.side_to_move()) {
+        println!("There is no {} piece on {}", color_name(lm.side_to_move()).to_lowercase(), square_name(pos));
+        return;
+    }
+    let destinations = lm.get_possible_moves(pos).cloned().unwrap_or_default();
+    lm.clear_selection();
+    let promotes = lm.get_board()[pos.0 as usize][pos.1 as usize].as_ref()
+        .is_some_and(|piece| piece.piece_type() == PieceTypes::Pawn);
+    let mut moves = vec![];
+    for to in destinations {
+        if promotes && (to.0 == 0 || to.0 == 7) {
+            for piece_type in lm.variant().promotions() {
+                moves.push(lm.create_move(pos, to, Some(piece_type)).to_san(lm));
+            }
+        } else {
+            moves.push(lm.create_move(pos, to, None).to_san(lm));
+        }
+    }
+    if moves.is_empty() {
+        println!("The piece on {} cannot move", square_name(pos));
+    } else {
+        println!("{}", moves.join(" "));
+    }
+}
+
+fn report_status(lm: &LogicManager) {
+    match lm.status() {
+        GameStatus::Ongoing => {
+            if lm.is_check(lm.side_to_move()) {
+                println!("Check!");
+            }
+            match lm.claimable_draw() {
+                Some(GameStatus::ThreefoldRepetition) => println!("The position occurred three times, type draw to claim a draw"),
+                Some(_) => println!("Fifty moves passed without a capture or pawn move, type draw to claim a draw"),
+                None => (),
+            }
+        }
+        GameStatus::Checkmate(winner) => println!("Checkmate, {} wins", color_name(winner).to_lowercase()),
+        GameStatus::VariantWin(winner) => println!("{} wins {}", color_name(winner), lm.variant().win_reason()),
+        GameStatus::Stalemate => println!("Draw by stalemate"),
+        GameStatus::FiftyMoveRule => println!("Draw claimed by the fifty move rule"),
+        GameStatus::ThreefoldRepetition => println!("Draw claimed by threefold repetition"),
+        GameStatus::SeventyFiveMoveRule => println!("Draw by the seventy-five move rule"),
+        GameStatus::FivefoldRepetition => println!("Draw by fivefold repetition"),
+        GameStatus::InsufficientMaterial => println!("Draw by insufficient material"),
+    }
+}
+
+//prints the board from white's side with the files and ranks around it
+fn print_board(lm: &LogicManager) {
+    println!();
+    println!("   a b c d e f g h");
+    for (i, row) in lm.get_board().iter().enumerate() {
+        print!("{}  ", 8 - i);
+        for sqr in row {
+            match &sqr {
+                Some(p) => p.print(),
+                None => print!("."),
+            }
+            print!(" ");
+        }
+        println!(" {}", 8 - i);
+    }
+    println!("   a b c d e f g h");
+    println!();
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "White",
+        Color::Black => "Black",
+    }
+}
@@ -0,0 +1,371 @@
+use crate::logic::{LogicManager, Move, GameStatus, Color};
+use crate::logic::piece::PieceTypes;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const MATE: i32 = 100_000;
+const MAX_PLY: i32 = 100;
+const INFINITY: i32 = 1_000_000;
+//below this much non pawn material on the board the king should walk to the center
+const ENDGAME_MATERIAL: i32 = 1_300;
+
+//piece square tables from white's point of view, indexed like the board so row 0 is the 8th rank
+const PAWN_TABLE: [[i32; 8]; 8] = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [50, 50, 50, 50, 50, 50, 50, 50],
+    [10, 10, 20, 30, 30, 20, 10, 10],
+    [5, 5, 10, 25, 25, 10, 5, 5],
+    [0, 0, 0, 20, 20, 0, 0, 0],
+    [5, -5, -10, 0, 0, -10, -5, 5],
+    [5, 10, 10, -20, -20, 10, 10, 5],
+    [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const KNIGHT_TABLE: [[i32; 8]; 8] = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20, 0, 0, 0, 0, -20, -40],
+    [-30, 0, 10, 15, 15, 10, 0, -30],
+    [-30, 5, 15, 20, 20, 15, 5, -30],
+    [-30, 0, 15, 20, 20, 15, 0, -30],
+    [-30, 5, 10, 15, 15, 10, 5, -30],
+    [-40, -20, 0, 5, 5, 0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+const BISHOP_TABLE: [[i32; 8]; 8] = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10, 0, 0, 0, 0, 0, 0, -10],
+    [-10, 0, 5, 10, 10, 5, 0, -10],
+    [-10, 5, 5, 10, 10, 5, 5, -10],
+    [-10, 0, 10, 10, 10, 10, 0, -10],
+    [-10, 10, 10, 10, 10, 10, 10, -10],
+    [-10, 5, 0, 0, 0, 0, 5, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+const ROOK_TABLE: [[i32; 8]; 8] = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [5, 10, 10, 10, 10, 10, 10, 5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [0, 0, 0, 5, 5, 0, 0, 0],
+];
+
+const QUEEN_TABLE: [[i32; 8]; 8] = [
+    [-20, -10, -10, -5, -5, -10, -10, -20],
+    [-10, 0, 0, 0, 0, 0, 0, -10],
+    [-10, 0, 5, 5, 5, 5, 0, -10],
+    [-5, 0, 5, 5, 5, 5, 0, -5],
+    [0, 0, 5, 5, 5, 5, 0, -5],
+    [-10, 5, 5, 5, 5, 5, 0, -10],
+    [-10, 0, 5, 0, 0, 0, 0, -10],
+    [-20, -10, -10, -5, -5, -10, -10, -20],
+];
+
+const KING_TABLE: [[i32; 8]; 8] = [
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [20, 20, 0, 0, 0, 0, 20, 20],
+    [20, 30, 10, 0, 0, 10, 30, 20],
+];
+
+const KING_ENDGAME_TABLE: [[i32; 8]; 8] = [
+    [-50, -40, -30, -20, -20, -30, -40, -50],
+    [-30, -20, -10, 0, 0, -10, -20, -30],
+    [-30, -10, 20, 30, 30, 20, -10, -30],
+    [-30, -10, 30, 40, 40, 30, -10, -30],
+    [-30, -10, 30, 40, 40, 30, -10, -30],
+    [-30, -10, 20, 30, 30, 20, -10, -30],
+    [-30, -30, 0, 0, 0, 0, -30, -30],
+    [-50, -30, -30, -30, -30, -30, -30, -50],
+];
+
+//how deep and how long the engine may search. The search stops at whichever limit is reached first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: u32,
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> SearchLimits {
+        SearchLimits { depth, time: None }
+    }
+
+    pub fn time(time: Duration) -> SearchLimits {
+        SearchLimits { depth: u32::MAX, time: Some(time) }
+    }
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        SearchLimits { depth: 16, time: Some(Duration::from_secs(2)) }
+    }
+}
+
+//what the engine found after finishing the search of one depth
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: i32, //in centipawns from the point of view of the side to move
+    pub nodes: u64,
+    pub time: Duration,
+    pub pv: Vec<Move>, //the best line found, starting with the best move
+}
+
+impl SearchInfo {
+    //the number of moves until mate if the score is a forced mate, negative when the side to move gets mated
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE - MAX_PLY {
+            return None;
+        }
+        let plies = MATE - self.score.abs();
+        Some(if self.score > 0 { (plies + 1) / 2 } else { -plies / 2 })
+    }
+}
+
+//an iterative deepening alpha-beta search over the moves of a LogicManager
+pub struct Engine {
+    deadline: Option<Instant>,
+    stop: Arc<AtomicBool>,
+    stopped: bool,
+    nodes: u64,
+    best_moves: HashMap<u64, Move>, //the best move found so far in each position, by position hash, searched first next time
+}
+
+impl Engine {
+    //searches the position and returns the best move found for the side to move, or None if the game is over
+    pub fn best_move(lm: &LogicManager, limits: SearchLimits) -> Option<Move> {
+        Engine::search(lm, limits, Arc::new(AtomicBool::new(false)), |_info| ())
+    }
+
+    //like best_move, but the search can also be ended early by setting stop, and report is called after every completed depth
+    pub fn search(lm: &LogicManager, limits: SearchLimits, stop: Arc<AtomicBool>, mut report: impl FnMut(&SearchInfo)) -> Option<Move> {
+        if lm.status().is_over() {
+            return None;
+        }
+        let start = Instant::now();
+        let mut engine = Engine {
+            deadline: limits.time.map(|time| start + time),
+            stop,
+            stopped: false,
+            nodes: 0,
+            best_moves: HashMap::new(),
+        };
+        let mut lm = lm.clone();
+        lm.clear_selection();
+
+        let mut moves = order_moves(&lm, lm.legal_moves(), None);
+        let mut best = moves[0];
+        for depth in 1..=limits.depth.max(1) {
+            let (score, pv) = engine.search_root(&mut lm, &moves, depth);
+            if engine.stopped {
+                break;
+            }
+            best = pv[0];
+            report(&SearchInfo { depth, score, nodes: engine.nodes, time: start.elapsed(), pv });
+            //a forced mate will not get any better by searching deeper
+            if score.abs() >= MATE - depth as i32 {
+                break;
+            }
+            moves = order_moves(&lm, moves, Some(best));
+        }
+        Some(best)
+    }
+
+    fn search_root(&mut self, lm: &mut LogicManager, moves: &[Move], depth: u32) -> (i32, Vec<Move>) {
+        let mut alpha = -INFINITY;
+        let mut pv = vec![moves[0]];
+        for mv in moves {
+            let mut line = vec![];
+            let undo = match lm.make_search_move(*mv) {
+                Some(undo) => undo,
+                //the rules of a variant may still reject a generated move
+                None => continue,
+            };
+            let score = -self.alpha_beta(lm, depth - 1, 1, -INFINITY, -alpha, &mut line);
+            lm.unmake_search_move(undo);
+            if self.stopped {
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                pv = vec![*mv];
+                pv.append(&mut line);
+            }
+        }
+        (alpha, pv)
+    }
+
+    //negamax alpha-beta, the score is from the point of view of the side to move. pv is filled with the best line found
+    fn alpha_beta(&mut self, lm: &mut LogicManager, depth: u32, ply: i32, mut alpha: i32, beta: i32, pv: &mut Vec<Move>) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        match lm.status() {
+            GameStatus::Ongoing => (),
+            status => return match status.winner() {
+                Some(winner) if winner == lm.side_to_move() => MATE - ply,
+                Some(_) => -(MATE - ply),
+                None => 0,
+            },
+        }
+        //the opponent would claim a draw rather than let a repetition go on
+        if lm.claimable_draw().is_some() {
+            return 0;
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(lm, alpha, beta);
+        }
+
+        let hash = lm.position_hash();
+        let moves = order_moves(lm, lm.legal_moves(), self.best_moves.get(&hash).copied());
+        for mv in moves {
+            let mut line = vec![];
+            let undo = match lm.make_search_move(mv) {
+                Some(undo) => undo,
+                None => continue,
+            };
+            let score = -self.alpha_beta(lm, depth - 1, ply + 1, -beta, -alpha, &mut line);
+            lm.unmake_search_move(undo);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                self.best_moves.insert(hash, mv);
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(mv);
+                pv.append(&mut line);
+            }
+        }
+        if let Some(best) = pv.first() {
+            self.best_moves.insert(hash, *best);
+        }
+        alpha
+    }
+
+    //keeps searching captures until the position is quiet, so the evaluation is not taken in the middle of an exchange
+    fn quiescence(&mut self, lm: &mut LogicManager, mut alpha: i32, beta: i32) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        let stand_pat = evaluate(lm);
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let captures = lm.legal_moves().into_iter()
+            .filter(|mv| mv.is_capture(lm) || mv.promotion.is_some())
+            .collect();
+        for mv in order_moves(lm, captures, None) {
+            let undo = match lm.make_search_move(mv) {
+                Some(undo) => undo,
+                None => continue,
+            };
+            let score = -self.quiescence(lm, -beta, -alpha);
+            lm.unmake_search_move(undo);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if self.stop.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+}
+
+//static evaluation of material and piece placement in centipawns, from the point of view of the side to move
+pub fn evaluate(lm: &LogicManager) -> i32 {
+    let board = lm.get_board();
+    let endgame = board.iter().flatten().flatten()
+        .filter(|piece| !matches!(piece.piece_type(), PieceTypes::Pawn | PieceTypes::King))
+        .map(|piece| piece_value(piece.piece_type()))
+        .sum::<i32>() <= ENDGAME_MATERIAL;
+
+    let mut score = 0;
+    for (i, row) in board.iter().enumerate() {
+        for (j, sqr) in row.iter().enumerate() {
+            if let Some(piece) = sqr {
+                //the tables are written for white, black reads them upside down
+                let row = if piece.color() == Color::White { i } else { 7 - i };
+                let table = match piece.piece_type() {
+                    PieceTypes::Pawn => &PAWN_TABLE,
+                    PieceTypes::Knight => &KNIGHT_TABLE,
+                    PieceTypes::Bishop => &BISHOP_TABLE,
+                    PieceTypes::Rook => &ROOK_TABLE,
+                    PieceTypes::Queen => &QUEEN_TABLE,
+                    PieceTypes::King if endgame => &KING_ENDGAME_TABLE,
+                    PieceTypes::King => &KING_TABLE,
+                };
+                let value = piece_value(piece.piece_type()) + table[row][j];
+                score += if piece.color() == Color::White { value } else { -value };
+            }
+        }
+    }
+    //pieces held in hand can be dropped anywhere, so they count at their full value
+    for color in [Color::White, Color::Black] {
+        let held = lm.variant().pocket(color).unwrap_or_default().iter()
+            .map(|(piece_type, count)| piece_value(*piece_type) * *count as i32)
+            .sum::<i32>();
+        score += if color == Color::White { held } else { -held };
+    }
+    if lm.side_to_move() == Color::White { score } else { -score }
+}
+
+fn piece_value(piece_type: PieceTypes) -> i32 {
+    match piece_type {
+        PieceTypes::Pawn => 100,
+        PieceTypes::Knight => 320,
+        PieceTypes::Bishop => 330,
+        PieceTypes::Rook => 500,
+        PieceTypes::Queen => 900,
+        PieceTypes::King => 0,
+    }
+}
+
+//sorts the moves so the most promising are searched first: the best move of the previous iteration,
+//then captures of valuable pieces by cheap ones, then promotions and finally quiet moves
+fn order_moves(lm: &LogicManager, mut moves: Vec<Move>, best: Option<Move>) -> Vec<Move> {
+    let board = lm.get_board();
+    moves.sort_by_cached_key(|mv| {
+        if Some(*mv) == best {
+            return i32::MIN;
+        }
+        let mut score = 0;
+        if mv.is_capture(lm) {
+            let victim = board[mv.to.row as usize][mv.to.col as usize].as_ref()
+                .map_or(PieceTypes::Pawn, |piece| piece.piece_type());
+            let attacker = board[mv.from.row as usize][mv.from.col as usize].as_ref().unwrap().piece_type();
+            score -= 10 * piece_value(victim) - piece_value(attacker) + 10_000;
+        }
+        if let Some(promotion) = mv.promotion {
+            score -= piece_value(promotion);
+        }
+        score
+    });
+    moves
+}
@@ -1,12 +1,23 @@
-use crate::logic::LogicManager;
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use crate::logic::{LogicManager, GameStatus, Move, MoveKind};
+use crate::logic::pgn::Pgn;
+use crate::engine::{Engine, SearchLimits};
 pub use bevy::{prelude::*};
 use bevy::input::mouse::MouseButtonInput;
+use bevy::app::AppExit;
+use bevy::window::WindowCloseRequested;
 use bevy_prototype_lyon::prelude::*;
-pub use bevy_kira_audio::{Audio, AudioPlugin, AudioSource};
+pub use bevy_kira_audio::{Audio, AudioChannel, AudioPlugin, AudioSource};
 use crate::logic::piece::PieceTypes;
+//bevy's prelude already has a Color for drawing
+use crate::logic::Color as PieceColor;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::collections::HashSet;
 
 pub use bevy::{
-    prelude::*,
     render::{
         camera::{ActiveCameras, Camera},
         pass::*,
@@ -19,10 +30,20 @@ pub use bevy::{
     window::{CreateWindow, WindowDescriptor, WindowId},
 };
 
+//the game saved with Ctrl+S and loaded with Ctrl+O, and the game saved when the window is closed
+pub const SAVE_FILE: &str = "saved_game.pgn";
+pub const AUTOSAVE_FILE: &str = "autosave.pgn";
+
 pub const SCREEN_HEIGHT: f32 = 650.;
 pub const SCREEN_WIDTH: f32 = 800.;
 const NUM_SQUARES: f32 = 8.;
 const SQUARE_SIZE: f32 = SCREEN_HEIGHT / NUM_SQUARES;
+//the pieces held in the pockets of variants with drops, in the order they are shown two to a row
+const POCKET_OPTIONS: [PieceTypes; 5] = [PieceTypes::Pawn, PieceTypes::Knight, PieceTypes::Bishop, PieceTypes::Rook, PieceTypes::Queen];
+const POCKET_SIZE: f32 = 65.;
+//explosions are shown for this long, and sound like a capture played at half the speed
+const EXPLOSION_SECONDS: f32 = 0.6;
+const EXPLOSION_CHANNEL: &str = "explosion";
 
 const HIDDEN_LAYER: usize = 0;
 const TILES_LAYER: usize = 1;
@@ -58,6 +79,7 @@ pub struct BackgroundColors {
     pub light: Handle<ColorMaterial>,
     pub dark: Handle<ColorMaterial>,
     pub yellow: Handle<ColorMaterial>,
+    pub red: Handle<ColorMaterial>,
 }
 
 #[derive(Clone)]
@@ -68,7 +90,6 @@ pub struct MoveSounds {
     pub check: Handle<AudioSource>,
 }
 
-pub struct Turn(pub bool);
 pub struct Moved(pub bool);
 pub struct Capture(pub bool);
 
@@ -77,8 +98,31 @@ pub struct PromotePawnOption {
     pub new_pos: (i8, i8),
 }
 
+//the piece chosen from the pocket of the side to move, to be dropped on one of the hinted squares
+pub struct PocketSelection(pub Option<PieceTypes>);
+
+//the color played by the computer, None when two people play against each other
+pub struct EngineOpponent {
+    pub color: Option<PieceColor>,
+    search: Option<Mutex<Receiver<Option<Move>>>>,
+    promotion: Option<PieceTypes>,
+}
+
+impl EngineOpponent {
+    pub fn new(color: Option<PieceColor>) -> EngineOpponent {
+        EngineOpponent { color, search: None, promotion: None }
+    }
+
+    //drops the search in progress, its result no longer matches the board
+    fn cancel(&mut self) {
+        self.search = None;
+        self.promotion = None;
+    }
+}
+
 pub struct Piece {
     piece_type: PieceTypes,
+    color: PieceColor,
 }
 
 pub struct Hint;
@@ -86,11 +130,29 @@ pub struct Selected;
 pub struct LastMove;
 pub struct Hidden;
 pub struct PromotePawn;
+pub struct ResultText;
+//a piece of a pocket, its highlight when chosen and the number held are all tagged with it
+#[derive(Clone, Copy)]
+pub struct PocketSlot {
+    color: PieceColor,
+    piece_type: PieceTypes,
+}
+pub struct PocketHighlight;
+//a square the variant marks, like the hill in King of the Hill
+pub struct VariantMark;
+//the state of the variant shown beside the board, like the checks given in three-check
+pub struct VariantText;
+//the flash over a square whose piece the variant took off the board, like the explosion of a capture in atomic chess
+pub struct Explosion(Timer);
 
 
-pub struct PieceOptionEvent(Vec<(i8, i8)>);
+//the selected square, None when a piece of the pocket is chosen, and the squares it can move to
+pub struct PieceOptionEvent(Option<(i8, i8)>, Vec<(i8, i8)>);
 pub struct MoveEvent(((i8, i8), (i8, i8)));
+pub struct DropEvent((PieceTypes, (i8, i8)));
 pub struct PawnPromotionEvent((i8, i8));
+//the squares whose pieces the variant took off the board after a move
+pub struct ExplosionEvent(Vec<(i8, i8)>);
 
 #[derive(StageLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum StageLabels {
@@ -102,14 +164,18 @@ pub enum StageLabels {
 
 pub fn setup(
     mut commands: Commands,
-    mut server: ResMut<AssetServer>,
+    server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut windows: ResMut<Windows>,
+    mut lm: ResMut<LogicManager>,
+    audio: Res<Audio>,
 ) {
     let bc = BackgroundColors {
         white: materials.add(Color::rgb(1., 1., 1.).into()),
         light: materials.add(Color::rgb(0.71, 0.533, 0.388).into()),
         dark: materials.add(Color::rgb(0.941, 0.851, 0.71).into()),
         yellow: materials.add(Color::rgba(1., 1., 0., 0.4).into()),
+        red: materials.add(Color::rgba(1., 0., 0., 0.4).into()),
     };
     let ms = MoveSounds {
         capture: server.load("capture.mp3"),
@@ -117,6 +183,7 @@ pub fn setup(
         check: server.load("move-check.mp3"),
         game_end: server.load("game-end.mp3"),
     };
+    audio.set_playback_rate_in_channel(0.5, &AudioChannel::new(EXPLOSION_CHANNEL.to_string()));
     commands.spawn_bundle(UiCameraBundle::default());
     commands.spawn_bundle(OrthographicCameraBundle::new_2d());
     commands.insert_resource(bc.clone());
@@ -125,11 +192,15 @@ pub fn setup(
         &mut commands,
         &bc,
     );
-    spawn_pieces(
-        &mut commands,
-        &mut server,
-        &mut materials,
-    )
+    spawn_pieces_from_board(&mut commands, &server, &mut materials, &lm);
+    spawn_pockets(&mut commands, &server, &mut materials, &bc);
+    spawn_variant_text(&mut commands, &server);
+    //a resumed game may already be over
+    let status = lm.status();
+    if status.is_over() {
+        lm.stop();
+        show_result(&mut commands, &server, &mut windows, status, lm.variant().win_reason());
+    }
 }
 
 fn spawn_board(
@@ -160,69 +231,288 @@ fn spawn_board(
     }
 }
 
-fn spawn_pieces(
+//spawns the sprites of every piece on the logical board
+fn spawn_pieces_from_board(
     commands: &mut Commands,
-    server: &mut ResMut<AssetServer>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
+    server: &AssetServer,
+    materials: &mut Assets<ColorMaterial>,
+    lm: &LogicManager,
 ) {
-    for y in [0, 1, 6, 7] {
-        let color = match y {
-            0..=3 => "dark",
-            _ => "light",
-        };
-
-        for x in 0..8 {
-            if y == 0 || y == 7 {
-                let (p, piece_type) = get_path(x, color);
+    for (y, row) in lm.get_board().iter().enumerate() {
+        for (x, sqr) in row.iter().enumerate() {
+            if let Some(piece) = sqr {
+                spawn_piece(commands, server, materials, piece.piece_type(), piece.color(), (y as i8, x as i8));
+            }
+        }
+    }
+}
 
-                let piece = Piece {
-                    piece_type,
-                };
-                commands
-                    .spawn_bundle(SpriteBundle {
-                        material: materials.add(server.load(&p[..]).into()),
-                        sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
-                        ..Default::default()
-                    })
-                    .insert(Position {x, y, z: PIECES_LAYER})
-                    .insert(piece);
-            } else {
-                let p = color.to_string() + "_pawn.png";
+fn spawn_piece(
+    commands: &mut Commands,
+    server: &AssetServer,
+    materials: &mut Assets<ColorMaterial>,
+    piece_type: PieceTypes,
+    color: PieceColor,
+    pos: (i8, i8),
+) {
+    let p = piece_path(piece_type, color);
+    commands
+        .spawn_bundle(SpriteBundle {
+            material: materials.add(server.load(&p[..]).into()),
+            sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+            ..Default::default()
+        })
+        .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: PIECES_LAYER})
+        .insert(Piece {
+            piece_type,
+            color,
+        });
+}
 
-                let piece = Piece {
-                    piece_type: PieceTypes::Pawn,
-                };
-                commands
-                    .spawn_bundle(SpriteBundle {
-                        material: materials.add(server.load(&p[..]).into()),
-                        sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+//spawns the pockets right of the board, black's at the top and white's at the bottom. They stay hidden
+//unless the variant being played has drops
+fn spawn_pockets(
+    commands: &mut Commands,
+    server: &AssetServer,
+    materials: &mut Assets<ColorMaterial>,
+    bc: &BackgroundColors,
+) {
+    let font = server.load("FiraSans-Bold.ttf");
+    for color in [PieceColor::White, PieceColor::Black] {
+        for piece_type in POCKET_OPTIONS {
+            let slot = PocketSlot { color, piece_type };
+            let (left, top) = pocket_slot_corner(slot);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: bc.yellow.clone(),
+                    sprite: Sprite::new(Vec2::new(POCKET_SIZE, POCKET_SIZE)),
+                    visible: Visible { is_visible: false, is_transparent: true },
+                    ..Default::default()
+                })
+                .insert(slot)
+                .insert(PocketHighlight);
+            let p = piece_path(piece_type, color);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: materials.add(ColorMaterial::modulated_texture(server.load(&p[..]), Color::WHITE)),
+                    sprite: Sprite::new(Vec2::new(POCKET_SIZE, POCKET_SIZE)),
+                    visible: Visible { is_visible: false, is_transparent: true },
+                    ..Default::default()
+                })
+                .insert(slot);
+            commands
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: Rect {
+                            left: Val::Px(left + POCKET_SIZE - 15.),
+                            top: Val::Px(top + POCKET_SIZE - 25.),
+                            ..Default::default()
+                        },
                         ..Default::default()
-                    })
-                    .insert(Position {x, y, z: PIECES_LAYER})
-                    .insert(piece);
-            }
+                    },
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(slot);
         }
     }
 }
 
-fn get_path(x: usize, color: &str) -> (String, PieceTypes) {
-    if x == 0 {
-        (color.to_string() + "_rook.png", PieceTypes::Rook)
-    } else if x == 1 {
-        (color.to_string() + "_knight.png", PieceTypes::Knight)
-    } else if x == 2 {
-        (color.to_string() + "_bishop.png", PieceTypes::Bishop)
-    } else if x == 3 {
-        (color.to_string() + "_queen.png", PieceTypes::Queen)
-    } else if x == 4 {
-        (color.to_string() + "_king.png", PieceTypes::King)
-    } else if x == 5 {
-        (color.to_string() + "_bishop.png", PieceTypes::Bishop)
-    } else if x == 6 {
-        (color.to_string() + "_knight.png", PieceTypes::Knight)
+//spawns the text right of the board that update_variant_marks fills with the state of the variant
+fn spawn_variant_text(
+    commands: &mut Commands,
+    server: &AssetServer,
+) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(25.),
+                    right: Val::Px(25.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: server.load("FiraSans-Bold.ttf"),
+                    font_size: 25.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Right,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        })
+        .insert(VariantText);
+}
+
+//the top left corner of a slot of the pockets, in pixels from the top left corner of the window
+fn pocket_slot_corner(slot: PocketSlot) -> (f32, f32) {
+    let index = POCKET_OPTIONS.iter().position(|piece_type| *piece_type == slot.piece_type).unwrap();
+    let left = SCREEN_HEIGHT + 10. + (index % 2) as f32 * POCKET_SIZE;
+    let row = (index / 2) as f32;
+    let top = match slot.color {
+        PieceColor::Black => row * POCKET_SIZE,
+        PieceColor::White => SCREEN_HEIGHT - (row + 1.) * POCKET_SIZE,
+    };
+    (left, top)
+}
+
+//the slot of the pockets under the cursor, whose position is measured from the bottom left corner of the window
+fn get_pocket_slot(pos: Vec2) -> Option<PocketSlot> {
+    let (x, y) = (pos.x, SCREEN_HEIGHT - pos.y);
+    [PieceColor::White, PieceColor::Black].iter()
+        .flat_map(|color| POCKET_OPTIONS.iter().map(move |piece_type| PocketSlot { color: *color, piece_type: *piece_type }))
+        .find(|slot| {
+            let (left, top) = pocket_slot_corner(*slot);
+            (left..left + POCKET_SIZE).contains(&x) && (top..top + POCKET_SIZE).contains(&y)
+        })
+}
+
+//the width of the window, which leaves room right of the board for the pockets of variants with drops and for
+//the state of variants that show one
+pub fn window_width(lm: &LogicManager) -> f32 {
+    if lm.variant().pocket(PieceColor::White).is_some() || lm.variant().state_text().is_some() {
+        SCREEN_WIDTH
     } else {
-        (color.to_string() + "_rook.png", PieceTypes::Rook)
+        SCREEN_HEIGHT
+    }
+}
+
+//shows how many pieces of each type both players hold and which one is chosen, and places the pockets beside the board.
+//Pieces that are not held are faded
+pub fn update_pockets(
+    lm: Res<LogicManager>,
+    windows: Res<Windows>,
+    mut pocket_selection: ResMut<PocketSelection>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query_hint: Query<Entity, With<Hint>>,
+    mut query_highlights: Query<(&PocketSlot, &mut Transform, &mut Visible), With<PocketHighlight>>,
+    mut query_pieces: Query<(&PocketSlot, &mut Transform, &mut Visible, &Handle<ColorMaterial>), Without<PocketHighlight>>,
+    mut query_counts: Query<(&PocketSlot, &mut Text)>,
+) {
+    let window = windows.get_primary().unwrap();
+    //the choice is dropped once its hints were cleared by another click, a move or an undo
+    if query_hint.iter().next().is_none() {
+        pocket_selection.0 = None;
+    }
+    let held = |slot: &PocketSlot| lm.variant().pocket(slot.color)
+        .and_then(|pocket| pocket.iter().find(|(piece_type, _count)| *piece_type == slot.piece_type).map(|(_piece_type, count)| *count));
+    let translation = |slot: &PocketSlot, z: usize| {
+        let (left, top) = pocket_slot_corner(*slot);
+        Vec3::new(left + POCKET_SIZE / 2. - window.width() / 2., window.height() / 2. - top - POCKET_SIZE / 2., z as f32)
+    };
+    for (slot, mut transform, mut visible) in query_highlights.iter_mut() {
+        transform.translation = translation(slot, SELECTIONS_LAYER);
+        visible.is_visible = slot.color == lm.side_to_move() && pocket_selection.0 == Some(slot.piece_type);
+    }
+    for (slot, mut transform, mut visible, material) in query_pieces.iter_mut() {
+        transform.translation = translation(slot, PIECES_LAYER);
+        let count = held(slot);
+        visible.is_visible = count.is_some();
+        if let Some(material) = materials.get_mut(material) {
+            material.color = if count.unwrap_or(0) > 0 { Color::WHITE } else { Color::rgba(1., 1., 1., 0.25) };
+        }
+    }
+    for (slot, mut text) in query_counts.iter_mut() {
+        text.sections[0].value = match held(slot) {
+            Some(count) if count > 0 => count.to_string(),
+            _ => String::new(),
+        };
+    }
+}
+
+//marks the squares the variant points out, rebuilt whenever they change, and writes the state of the variant
+//beside the board
+pub fn update_variant_marks(
+    mut commands: Commands,
+    lm: Res<LogicManager>,
+    bc: Res<BackgroundColors>,
+    query_marks: Query<(&Position, Entity), With<VariantMark>>,
+    mut query_text: Query<&mut Text, With<VariantText>>,
+) {
+    let marked = lm.variant().marked_squares(&lm);
+    let shown = query_marks.iter().map(|(pos, _e)| (pos.y as i8, pos.x as i8)).collect::<HashSet<_>>();
+    if shown != marked.iter().copied().collect::<HashSet<_>>() {
+        for (_pos, e) in query_marks.iter() {
+            commands.entity(e).despawn();
+        }
+        for pos in marked {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: bc.red.clone(),
+                    sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+                    ..Default::default()
+                })
+                .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: SELECTIONS_LAYER})
+                .insert(VariantMark);
+        }
+    }
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = lm.variant().state_text().unwrap_or_default();
+    }
+}
+
+//rebuilds the piece sprites from the logical board whenever they stop matching it, so the screen always shows
+//the position the game is really in
+pub fn resync_pieces(
+    mut commands: Commands,
+    lm: Res<LogicManager>,
+    promote_pawn_option: Res<PromotePawnOption>,
+    query_pieces: Query<(&Position, &Piece, Entity)>,
+    server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    //the pawn waiting for its promotion is hidden on purpose
+    if promote_pawn_option.happened {
+        return;
+    }
+    let board = lm.get_board();
+    let num_pieces = board.iter().flatten().filter(|sqr| sqr.is_some()).count();
+    let mut squares = HashSet::new();
+    let in_sync = query_pieces.iter().all(|(pos, piece, _e)| {
+        squares.insert((pos.x, pos.y)) && match &board[pos.y][pos.x] {
+            Some(p) => p.piece_type() == piece.piece_type && p.color() == piece.color,
+            None => false,
+        }
+    }) && squares.len() == num_pieces;
+    if in_sync {
+        return;
+    }
+    for (_pos, _piece, e) in query_pieces.iter() {
+        commands.entity(e).despawn();
     }
+    spawn_pieces_from_board(&mut commands, &server, &mut materials, &lm);
+}
+
+fn piece_path(piece_type: PieceTypes, color: PieceColor) -> String {
+    let color = match color {
+        PieceColor::White => "light",
+        PieceColor::Black => "dark",
+    };
+    let name = match piece_type {
+        PieceTypes::King => "king",
+        PieceTypes::Queen => "queen",
+        PieceTypes::Rook => "rook",
+        PieceTypes::Bishop => "bishop",
+        PieceTypes::Knight => "knight",
+        PieceTypes::Pawn => "pawn",
+    };
+    format!("{}_{}.png", color, name)
 }
 
 pub fn position_translation(
@@ -247,17 +537,23 @@ pub fn mouse_clicks (
     mut move_writer: EventWriter<MoveEvent>,
     mut piece_option_writer: EventWriter<PieceOptionEvent>,
     mut pawn_promotion_writer: EventWriter<PawnPromotionEvent>,
-    turn: Res<Turn>,
+    mut drop_writer: EventWriter<DropEvent>,
     query_hint: Query<(&Position, Entity), With<Hint>>,
     query_selected: Query<(&Position, Entity), With<Selected>>,
     promote_pawn_option: Res<PromotePawnOption>,
+    mut pocket_selection: ResMut<PocketSelection>,
+    engine: Res<EngineOpponent>,
 ) {
     let window = windows.get_primary().unwrap();
     for ev in evr_mousebtn.iter() {
+        if engine.color == Some(lm.side_to_move()) {
+            return;
+        }
         if ev.state.is_pressed() {
             if let Some(position) = window.cursor_position() {
                 let pos = get_sqr(position);
-                if pos.0 > 7 || pos.0 < 0 || pos.1 > 7 || pos.0 < 0 {
+                let pocket_slot = get_pocket_slot(position).filter(|_| lm.variant().pocket(lm.side_to_move()).is_some());
+                if (pos.0 > 7 || pos.0 < 0 || pos.1 > 7 || pos.1 < 0) && pocket_slot.is_none() {
                     return;
                 }
                 let hint_positions = query_hint.iter().map(|(p, _e)| (p.y as i8, p.x as i8)).collect::<Vec<(i8, i8)>>();
@@ -272,20 +568,27 @@ pub fn mouse_clicks (
                 }
                 if promote_pawn_option.happened {
                     pawn_promotion_writer.send(PawnPromotionEvent(pos));
+                } else if let Some(piece_type) = pocket_selection.0.filter(|_| hint_positions.contains(&pos)) {
+                    drop_writer.send(DropEvent((piece_type, pos)));
+                    pocket_selection.0 = None;
                 } else if lm.can_move() && hint_positions.contains(&pos) {
-                    move_writer.send(MoveEvent((*selected_position.iter().next().unwrap(), pos)));
+                    move_writer.send(MoveEvent((selected_position[0], pos)));
                 } else {
                     lm.clear_selection();
-                    if !selected_position.contains(&pos) {
-                        if let Some(piece_color) = lm.get_piece_color(pos) {
-                            if piece_color == turn.0 {
-                                if let Some(squares) = lm.get_possible_moves(pos) {
-                                    let mut v = (*squares).clone();
-                                    v.insert(0, pos);
-                                    piece_option_writer.send(PieceOptionEvent(v));
-                                }
+                    //clicking the chosen piece of the pocket again puts it back
+                    let chosen = pocket_selection.0.take();
+                    if let Some(slot) = pocket_slot {
+                        if slot.color == lm.side_to_move() && chosen != Some(slot.piece_type) {
+                            let squares = lm.drop_squares(slot.piece_type);
+                            if !squares.is_empty() {
+                                pocket_selection.0 = Some(slot.piece_type);
+                                piece_option_writer.send(PieceOptionEvent(None, squares));
                             }
                         }
+                    } else if !selected_position.contains(&pos) {
+                        if let Some(squares) = lm.get_possible_moves(pos) {
+                            piece_option_writer.send(PieceOptionEvent(Some(pos), (*squares).clone()));
+                        }
                     }
                 }
             }
@@ -304,17 +607,17 @@ pub fn piece_options(
     lm: Res<LogicManager>,
 ) {
     if let Some(piece_option_event) = piece_option_reader.iter().next() {
-        let mut it = piece_option_event.0.iter();
-        let pos = it.next().unwrap();
-        commands
-            .spawn_bundle(SpriteBundle {
-                material: background_colors.yellow.clone(),
-                sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
-                ..Default::default()
-            })
-            .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: SELECTIONS_LAYER})
-            .insert(Selected);
-        for hint_pos in it {
+        if let Some(pos) = piece_option_event.0 {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: background_colors.yellow.clone(),
+                    sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+                    ..Default::default()
+                })
+                .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: SELECTIONS_LAYER})
+                .insert(Selected);
+        }
+        for hint_pos in piece_option_event.1.iter() {
             if let Some(_color) = lm.get_piece_color(*hint_pos) {//if there is a piece at the hint location
                 let circle = shapes::Circle {
                     radius: 30.,
@@ -356,20 +659,20 @@ pub fn move_piece(
     mut query_pieces: Query<(&mut Position, Entity, &Piece)>,
     query_last_move: Query<Entity, With<LastMove>>,
     mut lm: ResMut<LogicManager>,
-    turn: Res<Turn>,
     bc: Res<BackgroundColors>,
     server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut promote_pawn_option: ResMut<PromotePawnOption>,
     mut capture: ResMut<Capture>,
     mut moved: ResMut<Moved>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
 ) {
     if let Some(move_event) = move_reader.iter().next() {
         let (prev_pos, new_pos) = move_event.0;
 
         for (mut pos, e, piece) in query_pieces.iter_mut() {
             if (pos.y as i8, pos.x as i8) == prev_pos && piece.piece_type == PieceTypes::Pawn && [0, 7].contains(&new_pos.0) {
-                promote_pawn_show_options(&mut commands, turn.0, new_pos, &bc, &server, &mut materials);
+                promote_pawn_show_options(&mut commands, lm.side_to_move(), new_pos, &lm.variant().promotions(), &bc, &server, &mut materials);
                 promote_pawn_option.happened = true;
                 promote_pawn_option.new_pos = new_pos;
                 commands.entity(e).insert(Hidden);
@@ -378,31 +681,33 @@ pub fn move_piece(
             }
         }
 
-        let res = lm.move_piece(new_pos);
+        let outcome = match lm.move_piece(new_pos) {
+            Some(outcome) => outcome,
+            None => return,
+        };
         moved.0 = true;
+        //a Chess960 king castles by moving onto its rook, so it may end up elsewhere than new_pos,
+        //and the rook may end up where the king stood
+        let destination = outcome.mv.destination();
+        //pieces the variant took off the board are gone too, including the piece that moved once it is on its destination
+        let removed = outcome.removed.iter().map(|(sqr, _piece_type)| sqr.pos()).collect::<Vec<_>>();
         for (mut pos, e, _piece) in query_pieces.iter_mut() {
-            if (pos.y as i8, pos.x as i8) == new_pos {
+            let sqr = (pos.y as i8, pos.x as i8);
+            if outcome.captured.is_some_and(|(captured, _)| captured.pos() == sqr) {
                 commands.entity(e).despawn();
                 capture.0 = true;
-            }
-            if let Some((other_prev_pos, other_new_pos)) = res {
-                if (pos.y as i8, pos.x as i8) == other_prev_pos {
-                    if other_new_pos == (-1, -1) {
-                        commands.entity(e).despawn();
-                        capture.0 = true;
-                    } else {
-                        pos.x = other_new_pos.1 as usize;
-                        pos.y = other_new_pos.0 as usize;
-                    }
-                }
+            } else if removed.contains(&sqr) || (sqr == prev_pos && removed.contains(&destination.pos())) {
+                commands.entity(e).despawn();
+            } else if let Some((_rook_from, rook_to)) = outcome.rook_move.filter(|(rook_from, _)| rook_from.pos() == sqr) {
+                pos.x = rook_to.col as usize;
+                pos.y = rook_to.row as usize;
+            } else if sqr == prev_pos {
+                pos.x = destination.col as usize;
+                pos.y = destination.row as usize;
             }
         }
-
-        for (mut pos, _e, _piece) in query_pieces.iter_mut() {
-            if (pos.y as i8, pos.x as i8) == prev_pos {
-                pos.x = new_pos.1 as usize;
-                pos.y = new_pos.0 as usize;
-            }
+        if !removed.is_empty() {
+            explosion_writer.send(ExplosionEvent(removed));
         }
 
         for e in query_last_move.iter() {
@@ -429,24 +734,51 @@ pub fn move_piece(
     }
 }
 
+//drops the piece chosen from the pocket of the side to move on the clicked square
+pub fn drop_piece(
+    mut commands: Commands,
+    mut drop_reader: EventReader<DropEvent>,
+    query_last_move: Query<Entity, With<LastMove>>,
+    mut lm: ResMut<LogicManager>,
+    bc: Res<BackgroundColors>,
+    server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut moved: ResMut<Moved>,
+) {
+    if let Some(drop_event) = drop_reader.iter().next() {
+        let (piece_type, pos) = drop_event.0;
+        let color = lm.side_to_move();
+        if lm.drop_piece(piece_type, pos).is_none() {
+            return;
+        }
+        moved.0 = true;
+        spawn_piece(&mut commands, &server, &mut materials, piece_type, color, pos);
+
+        for e in query_last_move.iter() {
+            commands.entity(e).despawn();
+        }
+        commands
+            .spawn_bundle(SpriteBundle {
+                material: bc.yellow.clone(),
+                sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+                ..Default::default()
+            })
+            .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: SELECTIONS_LAYER})
+            .insert(LastMove);
+    }
+}
+
 fn promote_pawn_show_options(
     commands: &mut Commands,
-    turn: bool,
+    turn: PieceColor,
     new_pos: (i8, i8),
+    options: &[PieceTypes],
     bc: &BackgroundColors,
     server: &Res<AssetServer>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
 ) {
-    let range = match turn {
-        false => (4..8).rev().collect::<Vec<_>>(),
-        true => (0..4).collect::<Vec<_>>(),
-    };
-    let piece_nums = [3, 1, 0, 2];
-    for (y, piece_type) in range.iter().zip(piece_nums) {
-        let (p, _piece_type) = get_path(piece_type, match turn {
-            true => "light",
-            false => "dark",
-        });
+    for (y, piece_type) in promotion_rows(turn, options.len()).iter().zip(options.iter()) {
+        let p = piece_path(*piece_type, turn);
 
         commands
             .spawn_bundle(SpriteBundle {
@@ -477,53 +809,37 @@ pub fn promote_pawn_choice(
     query_last_move: Query<Entity, With<LastMove>>,
     mut query_hidden: Query<(&mut Position, Entity), With<Hidden>>,
     query_pieces: Query<(&Position, Entity), (With<Piece>, Without<Hidden>)>,
-    turn: Res<Turn>,
     mut promote_pawn_option: ResMut<PromotePawnOption>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     server: Res<AssetServer>,
     bc: Res<BackgroundColors>,
     mut capture: ResMut<Capture>,
     mut moved: ResMut<Moved>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
 ) {
     if let Some(pawn_promotion_event) = pawn_promotion_reader.iter().next() {
         let pos_clicked = pawn_promotion_event.0;
         let (mut p_hidden, e_hidden) = query_hidden.iter_mut().next().unwrap();
-        let range = match turn.0 {
-            false => (4..8).rev().collect::<Vec<_>>(),
-            true => (0..4).collect::<Vec<_>>(),
-        };
+        let color = lm.side_to_move();
+        let options = lm.variant().promotions();
+        let range = promotion_rows(color, options.len());
         let new_pos = promote_pawn_option.new_pos;
-        let piece_types = vec![3, 1, 0,2];
         let mut promoted = false;
         if new_pos.1 == pos_clicked.1 {
             if let Some(index) = range.iter().position(|r| *r == pos_clicked.0) {
+                let piece_type = options[index];
+                let outcome = match lm.promote_pawn(new_pos, piece_type) {
+                    Some(outcome) => outcome,
+                    //the game did not accept the promotion, the pawn and the options stay for another choice
+                    None => return,
+                };
                 moved.0 = true;
-                println!("{}", index);
                 promoted = true;
-                let piece_type_usize: usize = *piece_types.get(index).unwrap();
-                let piece_type = match piece_type_usize {
-                    3 => PieceTypes::Queen,
-                    2 => PieceTypes::Bishop,
-                    1 => PieceTypes::Knight,
-                    0 => PieceTypes::Rook,
-                    _ => panic!("If this ever happens, something went very wrong"),
-                };
-                lm.promote_pawn(new_pos, piece_type);
+                let removed = outcome.removed.iter().map(|(sqr, _piece_type)| sqr.pos()).collect::<Vec<_>>();
                 commands.entity(e_hidden).despawn();
-                let (p, _piece_type) = get_path(piece_type_usize, match turn.0 {
-                    true => "light",
-                    false => "dark",
-                });
-                commands
-                    .spawn_bundle(SpriteBundle {
-                        material: materials.add(server.load(&p[..]).into()),
-                        sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
-                        ..Default::default()
-                    })
-                    .insert(Position {x: new_pos.1 as usize, y: new_pos.0 as usize, z: PIECES_LAYER})
-                    .insert(Piece {
-                        piece_type,
-                    });
+                if !removed.contains(&new_pos) {
+                    spawn_piece(&mut commands, &server, &mut materials, piece_type, color, new_pos);
+                }
 
                 for e in query_last_move.iter() {
                     commands.entity(e).despawn();
@@ -533,8 +849,13 @@ pub fn promote_pawn_choice(
                     if pos.x == new_pos.1 as usize && pos.y == new_pos.0 as usize {
                         commands.entity(e).despawn();
                         capture.0 = true;
+                    } else if removed.contains(&(pos.y as i8, pos.x as i8)) {
+                        commands.entity(e).despawn();
                     }
                 }
+                if !removed.is_empty() {
+                    explosion_writer.send(ExplosionEvent(removed));
+                }
                 commands
                     .spawn_bundle(SpriteBundle {
                         material: bc.yellow.clone(),
@@ -550,11 +871,11 @@ pub fn promote_pawn_choice(
                         sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
                         ..Default::default()
                     })
-                    .insert(Position {x: p_hidden.x as usize, y: p_hidden.y as usize, z: SELECTIONS_LAYER})
+                    .insert(Position {x: p_hidden.x, y: p_hidden.y, z: SELECTIONS_LAYER})
                     .insert(LastMove);
             }
         }
-        if promoted == false {
+        if !promoted {
             p_hidden.z = PIECES_LAYER;
             commands.entity(e_hidden).remove::<Hidden>();
             lm.clear_selection();
@@ -569,7 +890,6 @@ pub fn promote_pawn_choice(
 pub fn after_turn_updates(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut turn: ResMut<Turn>,
     move_sounds: Res<MoveSounds>,
     mut lm: ResMut<LogicManager>,
     mut capture: ResMut<Capture>,
@@ -582,58 +902,355 @@ pub fn after_turn_updates(
             true => move_sounds.capture.clone(),
             false => move_sounds.move_self.clone(),
         };
-        let is_draw = lm.is_draw();
-        if lm.is_check(turn.0) {
+        if lm.is_check(lm.side_to_move()) {
             to_play = move_sounds.check.clone();
         }
-        if lm.is_checkmate(turn.0) || is_draw {
+        let status = lm.status();
+        if status.is_over() {
             lm.stop();
-            let window = windows.get_primary_mut().unwrap();
-            window.set_resolution(SCREEN_WIDTH, SCREEN_HEIGHT);
             to_play = move_sounds.game_end.clone();
-            let to_display;
-            if is_draw {
-                to_display = "Draw";
-            } else if turn.0 == true {
-                to_display = "White\nwins";
-            } else {
-                to_display = "Black\nwins";
-            }
+            show_result(&mut commands, &asset_server, &mut windows, status, lm.variant().win_reason());
+        }
+        audio.play(to_play);
+        capture.0 = false;
+        moved.0 = false;
+    }
+}
+
+//flashes the squares of an explosion and plays its sound
+pub fn explosions(
+    mut commands: Commands,
+    mut explosion_reader: EventReader<ExplosionEvent>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    move_sounds: Res<MoveSounds>,
+    audio: Res<Audio>,
+) {
+    for explosion in explosion_reader.iter() {
+        for pos in explosion.0.iter() {
             commands
-                .spawn_bundle(TextBundle {
-                    style: Style {
-                        align_self: AlignSelf::FlexEnd,
-                        position_type: PositionType::Absolute,
-                        position: Rect {
-                            bottom: Val::Px(SCREEN_HEIGHT / 2. - 50.),
-                            right: Val::Px(25.0),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    },
-                    // Use the `Text::with_section` constructor
-                    text: Text::with_section(
-                        // Accepts a `String` or any type that converts into a `String`, such as `&str`
-                        to_display,
-                        TextStyle {
-                            font: asset_server.load("FiraSans-Bold.ttf"),
+                .spawn_bundle(SpriteBundle {
+                    material: materials.add(Color::rgba(1., 0.5, 0., 0.8).into()),
+                    sprite: Sprite::new(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+                    ..Default::default()
+                })
+                .insert(Position {x: pos.1 as usize, y: pos.0 as usize, z: HINTS_LAYER})
+                .insert(Explosion(Timer::from_seconds(EXPLOSION_SECONDS, false)));
+        }
+        audio.play_in_channel(move_sounds.capture.clone(), &AudioChannel::new(EXPLOSION_CHANNEL.to_string()));
+    }
+}
+
+//grows and fades the flashes of explosions, and removes them once they are over
+pub fn animate_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut query: Query<(Entity, &mut Explosion, &mut Transform, &Handle<ColorMaterial>)>,
+) {
+    for (e, mut explosion, mut transform, material) in query.iter_mut() {
+        explosion.0.tick(time.delta());
+        if explosion.0.finished() {
+            materials.remove(material);
+            commands.entity(e).despawn();
+            continue;
+        }
+        let progress = explosion.0.percent();
+        transform.scale = Vec3::splat(1. + progress * 0.5);
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_a(0.8 * (1. - progress));
+        }
+    }
+}
+
+//searches for the engine's move on another thread and plays it through the same events a click would send
+pub fn engine_turn(
+    mut lm: ResMut<LogicManager>,
+    mut engine: ResMut<EngineOpponent>,
+    mut move_writer: EventWriter<MoveEvent>,
+    mut pawn_promotion_writer: EventWriter<PawnPromotionEvent>,
+    mut drop_writer: EventWriter<DropEvent>,
+    promote_pawn_option: Res<PromotePawnOption>,
+) {
+    let color = match engine.color {
+        Some(color) => color,
+        None => return,
+    };
+    if promote_pawn_option.happened {
+        //the promotion options are shown, pick the one the engine chose
+        if let Some(piece_type) = engine.promotion.take() {
+            match promotion_square(&lm.variant().promotions(), piece_type, color, promote_pawn_option.new_pos.1) {
+                Some(sqr) => pawn_promotion_writer.send(PawnPromotionEvent(sqr)),
+                None => eprintln!("The engine promoted into a {:?}, which is not one of the promotion options", piece_type),
+            }
+        }
+        return;
+    }
+    if lm.side_to_move() != color || lm.is_stop() {
+        return;
+    }
+
+    let result = match &engine.search {
+        Some(search) => search.lock().unwrap().try_recv(),
+        None => {
+            let (sender, receiver) = mpsc::channel();
+            let position = lm.clone();
+            thread::spawn(move || {
+                let _ = sender.send(Engine::best_move(&position, SearchLimits::default()));
+            });
+            engine.search = Some(Mutex::new(receiver));
+            return;
+        }
+    };
+    match result {
+        Ok(Some(Move { to, kind: MoveKind::Drop(piece_type), .. })) => {
+            engine.search = None;
+            drop_writer.send(DropEvent((piece_type, to.pos())));
+        }
+        Ok(Some(mv)) => {
+            engine.search = None;
+            engine.promotion = mv.promotion;
+            lm.get_possible_moves(mv.from.pos());
+            move_writer.send(MoveEvent((mv.from.pos(), mv.to.pos())));
+        }
+        Ok(None) | Err(TryRecvError::Disconnected) => engine.search = None,
+        Err(TryRecvError::Empty) => (),
+    }
+}
+
+//the rows the promotion options are shown on, starting from the promotion square of color
+fn promotion_rows(color: PieceColor, count: usize) -> Vec<i8> {
+    match color {
+        PieceColor::White => (0..count as i8).collect(),
+        PieceColor::Black => (8 - count as i8..8).rev().collect(),
+    }
+}
+
+//the square of the promotion options that holds piece_type, matching the order shown by promote_pawn_show_options.
+//None if piece_type is not one of the options
+fn promotion_square(options: &[PieceTypes], piece_type: PieceTypes, color: PieceColor, col: i8) -> Option<(i8, i8)> {
+    let index = options.iter().position(|option| *option == piece_type)?;
+    Some((promotion_rows(color, options.len())[index], col))
+}
+
+//widens the window and writes how the game ended beside the board
+fn show_result(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    windows: &mut Windows,
+    status: GameStatus,
+    win_reason: &str,
+) {
+    let window = windows.get_primary_mut().unwrap();
+    window.set_resolution(SCREEN_WIDTH, SCREEN_HEIGHT);
+    let (to_display, reason) = status_text(status, win_reason);
+    let font = asset_server.load("FiraSans-Bold.ttf");
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::FlexEnd,
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(SCREEN_HEIGHT / 2. - 50.),
+                    right: Val::Px(25.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text {
+                sections: vec![
+                    TextSection {
+                        value: to_display.to_string(),
+                        style: TextStyle {
+                            font: font.clone(),
                             font_size: 50.0,
                             color: Color::WHITE,
                         },
-                        // Note: You can use `Default::default()` in place of the `TextAlignment`
-                        TextAlignment {
-                            horizontal: HorizontalAlign::Right,
-                            ..Default::default()
+                    },
+                    TextSection {
+                        value: reason,
+                        style: TextStyle {
+                            font,
+                            font_size: 25.0,
+                            color: Color::WHITE,
                         },
-                    ),
+                    },
+                ],
+                alignment: TextAlignment {
+                    horizontal: HorizontalAlign::Right,
                     ..Default::default()
-                });
+                },
+            },
+            ..Default::default()
+        })
+        .insert(ResultText);
+}
+
+//Ctrl+D claims a draw by threefold repetition or the fifty move rule, when one of them applies
+pub fn claim_draw(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    move_sounds: Res<MoveSounds>,
+    audio: Res<Audio>,
+    mut lm: ResMut<LogicManager>,
+    mut windows: ResMut<Windows>,
+) {
+    if !(keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl)) || !keys.just_pressed(KeyCode::D) {
+        return;
+    }
+    if let Some(status) = lm.claim_draw() {
+        lm.stop();
+        lm.clear_selection();
+        audio.play(move_sounds.game_end.clone());
+        show_result(&mut commands, &asset_server, &mut windows, status, lm.variant().win_reason());
+    }
+}
+
+//Ctrl+Z takes back the last move and Ctrl+Y replays it, after which the sprites are rebuilt from the logical board.
+//Against the engine a whole move of both sides is taken back, so it is the player's turn again
+pub fn undo_redo(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut lm: ResMut<LogicManager>,
+    mut engine: ResMut<EngineOpponent>,
+    mut promote_pawn_option: ResMut<PromotePawnOption>,
+    mut windows: ResMut<Windows>,
+    server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query_to_clear: Query<Entity, Or<(With<Piece>, With<Hint>, With<Selected>, With<LastMove>, With<PromotePawn>, With<ResultText>)>>,
+) {
+    if !keys.pressed(KeyCode::LControl) && !keys.pressed(KeyCode::RControl) {
+        return;
+    }
+    let changed = if keys.just_pressed(KeyCode::Z) {
+        let changed = lm.undo();
+        if changed && engine.color == Some(lm.side_to_move()) {
+            lm.undo();
         }
-        audio.play(to_play);
-        turn.0 = !turn.0;
-        capture.0 = false;
-        moved.0 = false;
+        changed
+    } else if keys.just_pressed(KeyCode::Y) {
+        let changed = lm.redo();
+        if changed && engine.color == Some(lm.side_to_move()) {
+            lm.redo();
+        }
+        changed
+    } else {
+        false
+    };
+    if !changed {
+        return;
+    }
+    engine.cancel();
+    promote_pawn_option.happened = false;
+    redraw_game(&mut commands, query_to_clear.iter(), &server, &mut materials, &mut windows, &lm);
+}
+
+//Ctrl+S saves the game to SAVE_FILE and Ctrl+O loads it back, replacing the game on the board
+pub fn save_load(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut lm: ResMut<LogicManager>,
+    mut engine: ResMut<EngineOpponent>,
+    mut promote_pawn_option: ResMut<PromotePawnOption>,
+    mut windows: ResMut<Windows>,
+    server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    query_to_clear: Query<Entity, Or<(With<Piece>, With<Hint>, With<Selected>, With<LastMove>, With<PromotePawn>, With<ResultText>)>>,
+) {
+    if !keys.pressed(KeyCode::LControl) && !keys.pressed(KeyCode::RControl) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::S) {
+        match Pgn::from_game(&lm).save(SAVE_FILE) {
+            Ok(()) => println!("Game saved to {}", SAVE_FILE),
+            Err(e) => eprintln!("Could not save the game to {}: {}", SAVE_FILE, e),
+        }
+    } else if keys.just_pressed(KeyCode::O) {
+        match LogicManager::load_pgn(SAVE_FILE) {
+            Ok(loaded) => {
+                *lm = loaded;
+                engine.cancel();
+                promote_pawn_option.happened = false;
+                redraw_game(&mut commands, query_to_clear.iter(), &server, &mut materials, &mut windows, &lm);
+                let status = lm.status();
+                if status.is_over() {
+                    lm.stop();
+                    show_result(&mut commands, &server, &mut windows, status, lm.variant().win_reason());
+                }
+                println!("Game loaded from {}", SAVE_FILE);
+            }
+            Err(e) => eprintln!("Could not load the game from {}: {}", SAVE_FILE, e),
+        }
+    }
+}
+
+//saves the game to AUTOSAVE_FILE when the window is closed, so it can be resumed on the next start
+pub fn autosave(
+    mut close_reader: EventReader<WindowCloseRequested>,
+    mut exit_reader: EventReader<AppExit>,
+    lm: Res<LogicManager>,
+) {
+    let closed = close_reader.iter().last().is_some();
+    let exited = exit_reader.iter().last().is_some();
+    if closed || exited {
+        if let Err(e) = Pgn::from_game(&lm).save(AUTOSAVE_FILE) {
+            eprintln!("Could not save the game to {}: {}", AUTOSAVE_FILE, e);
+        }
+    }
+}
+
+//replaces the pieces and markings on the screen with the position of lm, after it changed other than by a move
+fn redraw_game(
+    commands: &mut Commands,
+    to_clear: impl Iterator<Item = Entity>,
+    server: &AssetServer,
+    materials: &mut Assets<ColorMaterial>,
+    windows: &mut Windows,
+    lm: &LogicManager,
+) {
+    for e in to_clear {
+        commands.entity(e).despawn();
+    }
+    let window = windows.get_primary_mut().unwrap();
+    window.set_resolution(window_width(lm), SCREEN_HEIGHT);
+    spawn_pieces_from_board(commands, server, materials, lm);
+}
+
+//the headline and the explanation displayed beside the board once the game is over. win_reason explains a
+//win by the rules of the variant
+fn status_text(status: GameStatus, win_reason: &str) -> (&'static str, String) {
+    let (to_display, reason) = match status {
+        GameStatus::Checkmate(PieceColor::White) => ("White\nwins", "\nby checkmate"),
+        GameStatus::Checkmate(PieceColor::Black) => ("Black\nwins", "\nby checkmate"),
+        GameStatus::VariantWin(PieceColor::White) => return ("White\nwins", wrap_reason(win_reason)),
+        GameStatus::VariantWin(PieceColor::Black) => return ("Black\nwins", wrap_reason(win_reason)),
+        GameStatus::Stalemate => ("Draw", "\nby stalemate"),
+        GameStatus::FiftyMoveRule => ("Draw", "\nby fifty move\nrule"),
+        GameStatus::ThreefoldRepetition => ("Draw", "\nby threefold\nrepetition"),
+        GameStatus::SeventyFiveMoveRule => ("Draw", "\nby seventy-five\nmove rule"),
+        GameStatus::FivefoldRepetition => ("Draw", "\nby fivefold\nrepetition"),
+        GameStatus::InsufficientMaterial => ("Draw", "\nby insufficient\nmaterial"),
+        GameStatus::Ongoing => ("", ""),
+    };
+    (to_display, reason.to_string())
+}
+
+//breaks the explanation of a win into lines that fit beside the board, below the headline
+fn wrap_reason(reason: &str) -> String {
+    let mut res = String::new();
+    let mut line_len = 0;
+    for word in reason.split_whitespace() {
+        if line_len > 0 && line_len + word.len() < 15 {
+            res.push(' ');
+            line_len += 1;
+        } else {
+            res.push('\n');
+            line_len = 0;
+        }
+        res.push_str(word);
+        line_len += word.len();
     }
+    res
 }
 
 pub fn create_result_window(
@@ -672,15 +1289,13 @@ pub fn setup_result_window(
     //         false => "Black wins",
     //     }
     // };
-    let to_display = "Its working!";
     // get the non-default window id
     // get the non-default window id
     let window_id_old = windows
         .iter()
         .find(|w| w.id() != WindowId::default())
         .map(|w| w.id());
-    let window_id;
-    window_id = match window_id_old {
+    let window_id = match window_id_old {
         Some(window_id_old) => window_id_old,
         None => return,
     };
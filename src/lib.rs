@@ -0,0 +1,5 @@
+pub mod logic;
+pub mod engine;
+pub mod gui;
+pub mod cli;
+pub mod uci;
@@ -5,16 +5,81 @@ mod rook;
 mod bishop;
 mod queen;
 mod king;
+mod fen;
+mod history;
+mod san;
+mod moves;
+mod perft;
+mod zobrist;
+mod record;
+mod chess960;
+mod drops;
+mod crazyhouse;
+mod three_check;
+mod king_of_the_hill;
+mod atomic;
+pub mod variant;
+pub mod bitboard;
+pub mod pgn;
 
 use piece::{Piece, Board, PieceTypes};
+pub use piece::Color;
 use pawn::Pawn;
 use knight::Knight;
 use rook::Rook;
 use bishop::Bishop;
 use queen::Queen;
-use king::King;
+use king::{King, castling_rook, castling_squares};
+use history::Snapshot;
+use variant::{Variant, Standard};
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+pub use fen::{FenError, STARTING_FEN};
+pub use moves::{Move, MoveKind, MoveOutcome, Square, IllegalMove};
+pub use bitboard::Bitboards;
+pub use san::SanError;
+pub use record::{GameRecord, GameRecordError};
+pub use chess960::{chess960_back_rank, chess960_fen};
 
+//the state of the game from the point of view of the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate(Color), //color of the winner
+    VariantWin(Color), //won by a rule of the variant, color of the winner
+    Stalemate,
+    InsufficientMaterial,
+    //drawn automatically
+    SeventyFiveMoveRule,
+    FivefoldRepetition,
+    //drawn only once a player claims the draw
+    FiftyMoveRule,
+    ThreefoldRepetition,
+}
+
+impl GameStatus {
+    pub fn is_over(&self) -> bool {
+        *self != GameStatus::Ongoing
+    }
+
+    pub fn is_draw(&self) -> bool {
+        !matches!(self, GameStatus::Ongoing | GameStatus::Checkmate(_) | GameStatus::VariantWin(_))
+    }
+
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            GameStatus::Checkmate(color) | GameStatus::VariantWin(color) => Some(*color),
+            _ => None,
+        }
+    }
+}
 
+//serialized as its GameRecord, since the pieces on the board are trait objects
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "GameRecord", try_from = "GameRecord"))]
 pub struct LogicManager {
     board: Board,
     curr_selected: (i8, i8),
@@ -22,24 +87,44 @@ pub struct LogicManager {
     en_passant: Option<(i8, i8)>,
     black_king: (i8, i8),
     white_king: (i8, i8),
-    past_positions: Vec<(Vec<Vec<String>>, i8)>,
-    turns_since_capture: i8,
+    hash: u64,
+    past_positions: HashMap<u64, u8>, //how often each position occurred since the last capture or pawn move
+    halfmove_clock: u16, //moves since the last capture or pawn move
+    claimed_draw: Option<GameStatus>,
+    turn: Color,
+    fullmove_number: u16,
+    chess960: bool, //castling moves are written as the king moving onto its rook
+    variant: Box<dyn Variant>,
+    removed: Vec<(Square, PieceTypes)>, //the pieces the variant took off the board after the last move
+    start_fen: String,
+    moves: Vec<Move>,
+    san_moves: Vec<String>,
+    history: Vec<Snapshot>,
+    future: Vec<Snapshot>,
     stop: bool,
+    recording: bool, //false while a search makes moves it takes back right away
+    taken: Vec<((i8, i8), Box<dyn Piece>)>, //the pieces remove_piece took during a move of a search, to put them back
+}
+
+impl Default for LogicManager {
+    fn default() -> Self {
+        LogicManager::new()
+    }
 }
 
 impl LogicManager {
     //creates a new LogicManager instance and return it
     pub fn new() -> LogicManager {
         let mut board = Vec::with_capacity(8);
-        board.push(LogicManager::create_back_line(false));
+        board.push(LogicManager::create_back_line(Color::Black));
         for i in 1..7 {
             let mut row = Vec::with_capacity(8);
             for j in 0..8 {
                 if i == 1 {
-                    row.push(Pawn::new((i, j), false));
+                    row.push(Pawn::new((i, j), Color::Black));
                 }
                 else if i == 6 {
-                    row.push(Pawn::new((i, j), true));
+                    row.push(Pawn::new((i, j), Color::White));
                 }
                 else {
                     row.push(None);
@@ -47,7 +132,7 @@ impl LogicManager {
             }
             board.push(row);
         }
-        board.push(LogicManager::create_back_line(true));
+        board.push(LogicManager::create_back_line(Color::White));
 
         let mut res = LogicManager {
             board,
@@ -56,13 +141,27 @@ impl LogicManager {
             en_passant: None,
             black_king: (0, 4),
             white_king: (7, 4),
-            past_positions: vec![],
-            turns_since_capture: 0,
+            hash: 0,
+            past_positions: HashMap::new(),
+            halfmove_clock: 0,
+            claimed_draw: None,
+            turn: Color::White,
+            fullmove_number: 1,
+            chess960: false,
+            variant: Box::new(Standard),
+            removed: vec![],
+            start_fen: STARTING_FEN.to_string(),
+            moves: vec![],
+            san_moves: vec![],
+            history: vec![],
+            future: vec![],
             stop: false,
+            recording: true,
+            taken: vec![],
         };
-        res.add_board_to_list();
+        res.hash = res.compute_hash();
+        res.record_position();
         res
-
     }
 
     //gets a reference to the board
@@ -70,58 +169,85 @@ impl LogicManager {
         &self.board
     }
 
-    //gets all possible moves for a piece at pos. Returns None if there is no piece there. Must be called before moving
+    //selects the piece at pos and gets all of its possible moves. Returns None if there is no piece of the side to move there.
+    //Must be called before moving
     pub fn get_possible_moves(&mut self, pos: (i8, i8)) -> Option<&Vec<(i8, i8)>> {
-        if self.stop {
+        self.clear_selection();
+        if self.stop || self.get_piece_color(pos) != Some(self.turn) {
             return None;
         }
-        if self.curr_selected != (-1, -1) {
-            self.possible_moves.clear();
-        }
-        if let Some(piece) = self.board[pos.0 as usize][pos.1 as usize].as_ref() {
-            self.curr_selected = pos;
-            let king_pos = match piece.color() {
-                false => self.black_king,
-                true => self.white_king,
-            };
-            self.possible_moves = piece.get_possible_moves(&self.board, &self.en_passant, king_pos, false);
-            return Some(&self.possible_moves);
-        }
-        None
+        self.curr_selected = pos;
+        self.possible_moves = self.piece_moves(pos);
+        Some(&self.possible_moves)
     }
 
-    pub fn promote_pawn(&mut self, new_pos: (i8, i8), into: PieceTypes) {
+    //promotes the selected pawn moving to new_pos into the given piece. Must be called after get_possible_moves.
+    //Returns None if that is not a legal promotion
+    pub fn promote_pawn(&mut self, new_pos: (i8, i8), into: PieceTypes) -> Option<MoveOutcome> {
+        if !self.is_in_possible_moves(new_pos) || self.get_piece(self.curr_selected).piece_type() != PieceTypes::Pawn ||
+            ![0, 7].contains(&new_pos.0) || !self.variant.promotions().contains(&into) || into == PieceTypes::Pawn {
+            return None;
+        }
+        let mv = self.create_move(self.curr_selected, new_pos, Some(into));
+        let captured = self.board[new_pos.0 as usize][new_pos.1 as usize].as_ref().map(|piece| (mv.to, piece.piece_type()));
+        let san = self.begin_move(mv);
+        self.hash ^= self.state_hash();
+        self.toggle_piece_hash(self.curr_selected);
+        self.toggle_piece_hash(new_pos);
         let color = self.get_piece_color(self.curr_selected).unwrap();
         self.board[self.curr_selected.0 as usize][self.curr_selected.1 as usize] = None;//moving the piece on the board
-        self.board[new_pos.0 as usize][new_pos.1 as usize] = match into {
-            PieceTypes::Queen => Queen::new(new_pos, color),
-            PieceTypes::Rook => Rook::new(new_pos, color),
-            PieceTypes::Bishop => Bishop::new(new_pos, color),
-            PieceTypes::Knight => Knight::new(new_pos, color),
-            _ => panic!("Promoted pawn to illegal piece"),
-        };
+        self.board[new_pos.0 as usize][new_pos.1 as usize] = new_piece(into, new_pos, color);
+        self.toggle_piece_hash(new_pos);
+        self.curr_selected = (-1, -1);
+        self.en_passant = None;
+        self.halfmove_clock = 0;
+        self.clear_past_positions();
+        self.variant_after_move(mv, captured);
+        self.end_turn();
+        self.hash ^= self.state_hash();
+        self.record_position();
+        self.finish_move(mv, san);
+        Some(self.outcome(mv, PieceTypes::Pawn, captured, None))
     }
 
-    //moves a piece to new_pos. Must be called after get_possible_moves. Returns other piece to move if necessary.
-    pub fn move_piece(&mut self, new_pos: (i8, i8)) -> Option<((i8, i8), (i8, i8))> {
-        let mut to_return = None;
-        let first_move = self.get_piece(self.curr_selected).is_first_move();
+    //moves a piece to new_pos. Must be called after get_possible_moves. Returns everything the move changed on the board.
+    //Returns None without moving if new_pos is not one of the possible moves. Pawns reaching the last rank are not moved either,
+    //promote_pawn must be called instead
+    pub fn move_piece(&mut self, new_pos: (i8, i8)) -> Option<MoveOutcome> {
+        if !self.is_in_possible_moves(new_pos) {
+            return None;
+        }
+        let moved = self.get_piece(self.curr_selected).piece_type();
+        if moved == PieceTypes::Pawn && [0, 7].contains(&new_pos.0) {
+            return None;
+        }
+        let mv = self.create_move(self.curr_selected, new_pos, None);
+        let san = self.begin_move(mv);
+        self.hash ^= self.state_hash();
+
+        let castling_rook = self.castling_rook_of(self.curr_selected, new_pos);
+        let mut captured = match castling_rook {
+            Some(_) => None,
+            None => self.board[new_pos.0 as usize][new_pos.1 as usize].as_ref().map(|piece| (mv.to, piece.piece_type())),
+        };
+        let mut rook_move = None;
+        let is_pawn_move = self.get_piece(self.curr_selected).piece_type() == PieceTypes::Pawn;
         let used_en_passant = self.get_piece(self.curr_selected).took_using_en_passant(new_pos, &self.board);
         if used_en_passant {
             if let Some(piece_taken) = self.board[(new_pos.0 + 1) as usize][new_pos.1 as usize].as_ref() {
-                if !piece_taken.possible_en_passant().is_none() && piece_taken.color() == false {
+                if piece_taken.possible_en_passant().is_some() && piece_taken.color() == Color::Black {
+                    self.toggle_piece_hash((new_pos.0 + 1, new_pos.1));
                     self.board[(new_pos.0 + 1) as usize][new_pos.1 as usize] = None;
-                    to_return = Some((((new_pos.0 + 1), new_pos.1), (-1, -1)));
+                    captured = Some((Square::new(new_pos.0 + 1, new_pos.1), PieceTypes::Pawn));
                 }
             }
             if let Some(piece_taken) = self.board[(new_pos.0 - 1) as usize][new_pos.1 as usize].as_ref() {
-                if !piece_taken.possible_en_passant().is_none() && piece_taken.color() == true {
+                if piece_taken.possible_en_passant().is_some() && piece_taken.color() == Color::White {
+                    self.toggle_piece_hash((new_pos.0 - 1, new_pos.1));
                     self.board[(new_pos.0 - 1) as usize][new_pos.1 as usize] = None;
-                    to_return = Some((((new_pos.0 - 1), new_pos.1), (-1, -1)));
+                    captured = Some((Square::new(new_pos.0 - 1, new_pos.1), PieceTypes::Pawn));
                 }
             }
-        } else if self.get_piece(self.curr_selected).piece_type() == PieceTypes::Pawn && [0, 7].contains(&new_pos.0) {
-            return None;
         }
 
         self.get_mut_piece(self.curr_selected).move_piece(new_pos);//telling the piece it has moved
@@ -132,80 +258,164 @@ impl LogicManager {
             self.en_passant = None;
         }
 
-        if self.get_piece(self.curr_selected).piece_type() == PieceTypes::King {
-            let pos = match self.get_piece(self.curr_selected).color() {
-                true => self.white_king,
-                false => self.black_king,
-            };
-            if self.get_piece(self.curr_selected).color() {
-                self.white_king = new_pos;
-            } else {
-                self.black_king = new_pos;
+        let from = self.curr_selected;
+        if let Some(rook_pos) = castling_rook {
+            //the king and the rook are both taken off the board first, since either may land where the other stood
+            let (king_col, rook_col) = castling_squares(rook_pos.1 > from.1);
+            let (king_to, rook_to) = ((from.0, king_col), (from.0, rook_col));
+            self.toggle_piece_hash(from);
+            self.toggle_piece_hash(rook_pos);
+            let mut king = self.board[from.0 as usize][from.1 as usize].take().unwrap();
+            let mut rook = self.board[rook_pos.0 as usize][rook_pos.1 as usize].take().unwrap();
+            rook.move_piece(rook_to);//telling the piece it has moved
+            king.move_piece(king_to);
+            self.board[rook_to.0 as usize][rook_to.1 as usize] = Some(rook);
+            self.board[king_to.0 as usize][king_to.1 as usize] = Some(king);
+            self.toggle_piece_hash(rook_to);
+            self.toggle_piece_hash(king_to);
+            rook_move = Some((Square::from(rook_pos), Square::from(rook_to)));
+            match self.turn {
+                Color::White => self.white_king = king_to,
+                Color::Black => self.black_king = king_to,
             }
-            if first_move && new_pos == (pos.0, 6) {
-                self.get_mut_piece((pos.0, 7)).move_piece((pos.0, 5));//telling the piece it has moved
-                self.board[pos.0 as usize][5] =
-                    Some(self.board[pos.0 as usize][7].as_ref().unwrap().clone());
-                self.board[pos.0 as usize][7] = None;//moving the piece on the board
-                to_return = Some(((pos.0, 7), (pos.0, 5)));
-            } else if first_move && new_pos == (pos.0, 2) {
-                self.get_mut_piece((pos.0, 0)).move_piece((pos.0, 3));//telling the piece it has moved
-                self.board[pos.0 as usize][3] =
-                    Some(self.board[pos.0 as usize][0].as_ref().unwrap().clone());
-                self.board[pos.0 as usize][0] = None;//moving the piece on the board
-                to_return = Some(((pos.0, 0), (pos.0, 3)));
+        } else {
+            if self.get_piece(from).piece_type() == PieceTypes::King {
+                match self.turn {
+                    Color::White => self.white_king = new_pos,
+                    Color::Black => self.black_king = new_pos,
+                }
             }
+            self.toggle_piece_hash(new_pos);
+            self.toggle_piece_hash(from);
+            self.board[new_pos.0 as usize][new_pos.1 as usize] = self.board[from.0 as usize][from.1 as usize].take();//moving the piece on the board
+            self.toggle_piece_hash(new_pos);
         }
-
-        let was_piece_taken =  self.board[new_pos.0 as usize][new_pos.1 as usize].is_some();
-
-        self.board[new_pos.0 as usize][new_pos.1 as usize] =
-            Some(self.board[self.curr_selected.0 as usize][self.curr_selected.1 as usize].as_ref().unwrap().clone());
-        self.board[self.curr_selected.0 as usize][self.curr_selected.1 as usize] = None;//moving the piece on the board
         self.curr_selected = (-1, -1);
 
-        if was_piece_taken {
-            self.turns_since_capture = 0;
-            self.past_positions.clear();
+        //captures and pawn moves can not be undone, so no earlier position can repeat after them
+        if captured.is_some() || is_pawn_move {
+            self.halfmove_clock = 0;
+            self.clear_past_positions();
         } else {
-            self.turns_since_capture += 1;
-            self.add_board_to_list();
+            self.halfmove_clock += 1;
         }
-        to_return
+        self.variant_after_move(mv, captured);
+        self.end_turn();
+        self.hash ^= self.state_hash();
+        self.record_position();
+        self.finish_move(mv, san);
+        Some(self.outcome(mv, moved, captured, rook_move))
     }
 
     pub fn is_in_possible_moves(&self, pos: (i8, i8)) -> bool {
         self.possible_moves.contains(&pos)
     }
 
-    pub fn is_check(&self, color: bool) -> bool {
-        let other_king_pos = match color {
-            true => self.black_king,
-            false => self.white_king,
-        };
-        self.get_piece(other_king_pos)
-            .as_any().downcast_ref::<King>().unwrap()
-            .is_check(&self.board, (-1, -1), (-1, -1), &self.en_passant, other_king_pos)
+    //true if the king of color is in check, which is when it is attacked unless the variant says otherwise
+    pub fn is_check(&self, color: Color) -> bool {
+        self.variant.is_check(&Bitboards::from_board(&self.board), color)
     }
 
-    pub fn is_checkmate(&self, color: bool) -> bool {
-        let other_king_pos = match color {
-            true => self.black_king,
-            false => self.white_king,
-        };
-        self.get_piece(other_king_pos)
-            .as_any().downcast_ref::<King>().unwrap()
-            .is_checkmate(&self.board, &self.en_passant, other_king_pos)
+    //true if the king of color is checkmated
+    pub fn is_checkmate(&self, color: Color) -> bool {
+        self.is_check(color) && !self.has_legal_moves(color)
     }
 
     pub fn is_draw(&self) -> bool {
-        if self.turns_since_capture >= 100 {
-            true
-        } else if self.past_positions.iter().find(|(_board, n)| *n >= 3).is_some() {
-            true
+        self.status().is_draw()
+    }
+
+    //returns whether the game is over, and how, for the side to move. Threefold repetition and the fifty move rule
+    //only end the game once claimed with claim_draw, fivefold repetition and the seventy-five move rule end it automatically
+    pub fn status(&self) -> GameStatus {
+        if let Some(draw) = self.claimed_draw {
+            draw
+        } else if let Some(status) = self.variant.status(self) {
+            status
+        } else if !self.has_legal_moves(self.turn) {
+            if self.is_check(self.turn) {
+                GameStatus::Checkmate(self.turn.opposite())
+            } else {
+                GameStatus::Stalemate
+            }
+        } else if self.is_insufficient_material() && self.variant.material_can_draw(self) {
+            GameStatus::InsufficientMaterial
+        } else if self.halfmove_clock >= 150 {
+            GameStatus::SeventyFiveMoveRule
+        } else if self.repetitions() >= 5 {
+            GameStatus::FivefoldRepetition
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+
+    //the draw a player may claim in the current position, if any
+    pub fn claimable_draw(&self) -> Option<GameStatus> {
+        if self.repetitions() >= 3 {
+            Some(GameStatus::ThreefoldRepetition)
+        } else if self.halfmove_clock >= 100 {
+            Some(GameStatus::FiftyMoveRule)
         } else {
-            false
+            None
+        }
+    }
+
+    //ends the game in a draw if one can be claimed, and returns the reason. Returns None if there is nothing to claim
+    pub fn claim_draw(&mut self) -> Option<GameStatus> {
+        if self.status().is_over() {
+            return None;
+        }
+        self.claimed_draw = self.claimable_draw();
+        self.claimed_draw
+    }
+
+    //how many times the current position has occurred, including now
+    pub fn repetitions(&self) -> usize {
+        self.past_positions.get(&self.hash).copied().unwrap_or(0) as usize
+    }
+
+    fn record_position(&mut self) {
+        *self.past_positions.entry(self.hash).or_insert(0) += 1;
+    }
+
+    //called after a capture or pawn move. A search keeps the earlier positions, which can not come up again anyway,
+    //so taking its move back only has to forget the position after it
+    fn clear_past_positions(&mut self) {
+        if self.recording {
+            self.past_positions.clear();
+        }
+    }
+
+    //true if any piece of color can make a legal move, or color can drop a piece
+    fn has_legal_moves(&self, color: Color) -> bool {
+        (0..8).any(|i| (0..8).any(|j| self.get_piece_color((i, j)) == Some(color) && !self.piece_moves((i, j)).is_empty())) ||
+            self.can_drop(color)
+    }
+
+    //true if no sequence of legal moves can end in checkmate, which FIDE rules a draw. That is the case with only the kings,
+    //kings and a single knight, or kings and any number of bishops which all stand on squares of the same color
+    fn is_insufficient_material(&self) -> bool {
+        let mut knights = 0;
+        let mut bishop_square_colors = vec![];
+        for (i, row) in self.board.iter().enumerate() {
+            for (j, sqr) in row.iter().enumerate() {
+                match sqr.as_ref().map(|piece| piece.piece_type()) {
+                    None | Some(PieceTypes::King) => (),
+                    Some(PieceTypes::Knight) => knights += 1,
+                    Some(PieceTypes::Bishop) => bishop_square_colors.push((i + j) % 2),
+                    Some(_) => return false,
+                }
+            }
         }
+        match knights {
+            0 => bishop_square_colors.windows(2).all(|colors| colors[0] == colors[1]),
+            1 => bishop_square_colors.is_empty(),
+            _ => false,
+        }
+    }
+
+    pub fn side_to_move(&self) -> Color {
+        self.turn
     }
 
     pub fn can_move(&self) -> bool {
@@ -217,12 +427,8 @@ impl LogicManager {
         self.curr_selected = (-1, -1);
     }
 
-    pub fn get_piece_color(&self, pos: (i8, i8)) -> Option<bool> {
-        if let Some(_piece) = self.board[pos.0 as usize][pos.1 as usize].as_ref() {
-            Some(self.get_piece(pos).color())
-        } else {
-            None
-        }
+    pub fn get_piece_color(&self, pos: (i8, i8)) -> Option<Color> {
+        self.board[pos.0 as usize][pos.1 as usize].as_ref().map(|piece| piece.color())
     }
 
     pub fn stop(&mut self) {
@@ -233,14 +439,162 @@ impl LogicManager {
         self.stop
     }
 
-    fn get_piece(&self, pos: (i8, i8)) -> &Box<dyn Piece> {
+    fn get_piece(&self, pos: (i8, i8)) -> &dyn Piece {
         if let Some(piece) = self.board[pos.0 as usize][pos.1 as usize].as_ref() {
-            piece
+            piece.as_ref()
         } else{
             panic!("Called get piece on empty square!");
         }
     }
 
+    //returns the moves played so far
+    pub fn get_moves(&self) -> &Vec<Move> {
+        &self.moves
+    }
+
+    //returns the moves played so far in Standard Algebraic Notation
+    pub fn get_san_moves(&self) -> &Vec<String> {
+        &self.san_moves
+    }
+
+    //returns the position the game started from in Forsyth-Edwards Notation
+    pub fn get_start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    //gets all legal moves for the piece at pos without changing the current selection
+    fn piece_moves(&self, pos: (i8, i8)) -> Vec<(i8, i8)> {
+        match self.board[pos.0 as usize][pos.1 as usize].as_ref() {
+            //a side whose king the variant took off the board has lost, and its pieces can not move anymore
+            Some(piece) if self.has_king(piece.color()) => {
+                let king_pos = self.king_pos(piece.color());
+                let mut moves = match self.variant.piece_moves(self, pos) {
+                    Some(moves) => moves,
+                    None => piece.get_possible_moves(&self.board, &self.en_passant, king_pos, false),
+                };
+                //kings castle onto their own rook, outside of Chess960 that is written as the king moving two squares
+                if piece.piece_type() == PieceTypes::King && !self.chess960 {
+                    for to in moves.iter_mut() {
+                        if self.get_piece_color(*to) == Some(piece.color()) {
+                            *to = (to.0, castling_squares(to.1 > pos.1).0);
+                        }
+                    }
+                }
+                self.variant.filter_moves(self, pos, moves)
+            }
+            _ => vec![],
+        }
+    }
+
+    //the moves of the piece at pos if it did not matter whether its king is attacked afterwards, for variants that
+    //decide that themselves. Castling, which is checked by the king itself, is included with the king onto its rook
+    pub fn pseudo_legal_moves(&self, pos: (i8, i8)) -> Vec<(i8, i8)> {
+        let piece = match self.board[pos.0 as usize][pos.1 as usize].as_ref() {
+            Some(piece) => piece,
+            None => return vec![],
+        };
+        let king_pos = self.king_pos(piece.color());
+        let mut moves = piece.get_possible_moves(&self.board, &self.en_passant, king_pos, true);
+        if piece.piece_type() == PieceTypes::King {
+            let castling = piece.get_possible_moves(&self.board, &self.en_passant, king_pos, false).into_iter()
+                .filter(|to| self.get_piece_color(*to) == Some(piece.color()));
+            moves.extend(castling);
+        }
+        moves
+    }
+
+    //false once the variant took the king of color off the board
+    pub(crate) fn has_king(&self, color: Color) -> bool {
+        let king_pos = self.king_pos(color);
+        match self.board[king_pos.0 as usize][king_pos.1 as usize].as_ref() {
+            Some(piece) => piece.piece_type() == PieceTypes::King && piece.color() == color,
+            None => false,
+        }
+    }
+
+    //lets the variant apply its rules to the move just made. The variant is taken out while it runs,
+    //so it can change the game through the LogicManager
+    fn variant_after_move(&mut self, mv: Move, captured: Option<(Square, PieceTypes)>) {
+        self.removed.clear();
+        let mut variant = std::mem::replace(&mut self.variant, Box::new(Standard));
+        variant.after_move(self, mv, captured);
+        self.variant = variant;
+    }
+
+    //removes the piece at pos, returning its type. Meant for the rules of variants, the move being made is not changed
+    pub fn remove_piece(&mut self, pos: (i8, i8)) -> Option<PieceTypes> {
+        self.toggle_piece_hash(pos);
+        let piece = self.board[pos.0 as usize][pos.1 as usize].take()?;
+        let piece_type = piece.piece_type();
+        self.removed.push((pos.into(), piece_type));
+        if !self.recording {
+            self.taken.push((pos, piece));
+        }
+        Some(piece_type)
+    }
+
+    //the rules the game is played by
+    pub fn variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
+    //starts a game of the given variant from its starting position
+    pub fn with_variant(variant: Box<dyn Variant>) -> LogicManager {
+        let fen = variant.starting_fen();
+        LogicManager::from_fen_with_variant(&fen, variant).expect("invalid starting position of the variant")
+    }
+
+    //creates a game of the given variant from a position in Forsyth-Edwards Notation
+    pub fn from_fen_with_variant(fen: &str, variant: Box<dyn Variant>) -> Result<LogicManager, FenError> {
+        LogicManager::parse_fen(fen, false, variant)
+    }
+
+    //the square of the rook the king at from castles with by moving to to, None if that is not a castling move.
+    //Must be called before the move is made
+    pub(crate) fn castling_rook_of(&self, from: (i8, i8), to: (i8, i8)) -> Option<(i8, i8)> {
+        let king = self.board[from.0 as usize][from.1 as usize].as_ref()?;
+        if king.piece_type() != PieceTypes::King || !king.is_first_move() || from.0 != to.0 {
+            return None;
+        }
+        if self.chess960 {
+            let rook = self.board[to.0 as usize][to.1 as usize].as_ref()?;
+            (rook.piece_type() == PieceTypes::Rook && rook.color() == king.color()).then_some(to)
+        } else if (to.1 - from.1).abs() == 2 {
+            castling_rook(&self.board, from, king.color(), to.1 > from.1).map(|col| (from.0, col))
+        } else {
+            None
+        }
+    }
+
+    //the column of the rook color can still castle with on one side, None once the king or that rook has moved
+    pub(crate) fn castling_rook_col(&self, color: Color, kingside: bool) -> Option<i8> {
+        let king_pos = self.king_pos(color);
+        if !self.has_king(color) || !self.get_piece(king_pos).is_first_move() {
+            return None;
+        }
+        castling_rook(&self.board, king_pos, color, kingside)
+    }
+
+    //true if the game is played with the Chess960 rules, where castling is written as the king moving onto its rook
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    //passes the turn to the other color
+    fn end_turn(&mut self) {
+        self.turn = self.turn.opposite();
+        if self.turn == Color::White {
+            self.fullmove_number += 1;
+        }
+    }
+
+    pub(crate) fn king_pos(&self, color: Color) -> (i8, i8) {
+        match color {
+            Color::White => self.white_king,
+            Color::Black => self.black_king,
+        }
+    }
+
     fn get_mut_piece(&mut self, pos: (i8, i8)) -> &mut Box<dyn Piece> {
         if let Some(piece) = self.board[pos.0 as usize][pos.1 as usize].as_mut() {
             piece
@@ -249,43 +603,21 @@ impl LogicManager {
         }
     }
 
-    fn create_back_line(color: bool) -> Vec<Option<Box<dyn Piece>>> {
-        let i = match color { false => 0, true => 7};
+    fn create_back_line(color: Color) -> Vec<Option<Box<dyn Piece>>> {
+        let i = match color { Color::Black => 0, Color::White => 7};
         vec![Rook::new((i, 0), color), Knight::new((i, 1), color), Bishop::new((i, 2), color), Queen::new((i, 3), color), King::new((i, 4), color), Bishop::new((i, 5), color), Knight::new((i, 6), color), Rook::new((i, 7), color)]
     }
+}
 
-    fn add_board_to_list(&mut self) {
-        let mut simplified_board = vec![];
-        for row in &self.board {
-            let mut simplified_row = vec![];
-            for sqr in row {
-                let mut to_add;
-                if let Some(piece) = sqr {
-                    to_add = match piece.piece_type() {
-                        PieceTypes::King => "k",
-                        PieceTypes::Queen => "q",
-                        PieceTypes::Rook => "r",
-                        PieceTypes::Bishop => "b",
-                        PieceTypes::Knight => "n",
-                        PieceTypes::Pawn => "p",
-                    }.to_string();
-                    to_add += match piece.color() {
-                        true => "w",
-                        false => "b",
-                    };
-                } else {
-                    to_add = " ".to_string();
-                }
-                simplified_row.push(to_add);
-            }
-            simplified_board.push(simplified_row);
-        }
-        let pos = self.past_positions.iter().position(|(b, _i)| b == &simplified_board);
-        if let Some(pos) = pos {
-            self.past_positions[pos].1 += 1;
-        } else {
-            self.past_positions.push((simplified_board, 1));
-        }
+//creates a piece of the given type and color standing on pos
+fn new_piece(piece_type: PieceTypes, pos: (i8, i8), color: Color) -> Option<Box<dyn Piece>> {
+    match piece_type {
+        PieceTypes::King => King::new(pos, color),
+        PieceTypes::Queen => Queen::new(pos, color),
+        PieceTypes::Rook => Rook::new(pos, color),
+        PieceTypes::Bishop => Bishop::new(pos, color),
+        PieceTypes::Knight => Knight::new(pos, color),
+        PieceTypes::Pawn => Pawn::new(pos, color),
     }
 }
 
@@ -0,0 +1,99 @@
+use super::piece::{PieceTypes, Color, is_valid_pos};
+use super::bitboard::{Bitboards, square, king_attacks};
+use super::variant::Variant;
+use super::{LogicManager, GameStatus, Move, Square};
+use std::any::Any;
+
+//a capture explodes the capturing piece, the captured one and every piece but the pawns around them. Kings can not
+//capture, and exploding the king of the opponent wins. Kings standing next to each other can not be checked, since
+//capturing either would explode both
+#[derive(Clone, Default)]
+pub struct Atomic;
+
+impl Atomic {
+    //true if moving the piece on from to to does not explode the own king or leave it in check.
+    //Moves that explode the king of the opponent are always legal
+    fn is_legal(&self, position: &Bitboards, from: (i8, i8), to: (i8, i8)) -> bool {
+        let (piece_type, color) = match position.piece_at(square(from)) {
+            Some(piece) => piece,
+            None => return false,
+        };
+        let target = position.piece_at(square(to));
+        //the king moves onto its own rook when castling, which it already checked
+        if target.is_some_and(|(_piece_type, target_color)| target_color == color) {
+            return true;
+        }
+        let is_capture = target.is_some() || (piece_type == PieceTypes::Pawn && from.1 != to.1);
+        if is_capture && piece_type == PieceTypes::King {
+            return false;
+        }
+        let mut after = *position;
+        after.move_piece(from, to);
+        if is_capture {
+            after.explode(to);
+        }
+        !self.is_check(&after, color)
+    }
+}
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "Atomic"
+    }
+
+    fn piece_moves(&self, lm: &LogicManager, from: (i8, i8)) -> Option<Vec<(i8, i8)>> {
+        let position = Bitboards::from_board(lm.get_board());
+        Some(lm.pseudo_legal_moves(from).into_iter().filter(|to| self.is_legal(&position, from, *to)).collect())
+    }
+
+    //a king that exploded counts as checked, so no move may explode the own king
+    fn is_check(&self, position: &Bitboards, color: Color) -> bool {
+        let king = position.pieces(PieceTypes::King, color);
+        let enemy_king = position.pieces(PieceTypes::King, color.opposite());
+        if king == 0 {
+            return true;
+        }
+        let sqr = king.trailing_zeros() as usize;
+        enemy_king != 0 && king_attacks(sqr) & enemy_king == 0 && position.is_attacked(sqr, color.opposite())
+    }
+
+    fn after_move(&mut self, lm: &mut LogicManager, mv: Move, captured: Option<(Square, PieceTypes)>) {
+        if captured.is_none() {
+            return;
+        }
+        let center = mv.to.pos();
+        lm.remove_piece(center);
+        for row in center.0 - 1..=center.0 + 1 {
+            for col in center.1 - 1..=center.1 + 1 {
+                let explodes = is_valid_pos((row, col)) && lm.get_board()[row as usize][col as usize].as_ref()
+                    .is_some_and(|piece| piece.piece_type() != PieceTypes::Pawn);
+                if explodes {
+                    lm.remove_piece((row, col));
+                }
+            }
+        }
+    }
+
+    fn status(&self, lm: &LogicManager) -> Option<GameStatus> {
+        [Color::White, Color::Black].iter().find(|color| !lm.has_king(color.opposite())).map(|color| GameStatus::VariantWin(*color))
+    }
+
+    fn win_reason(&self) -> &'static str {
+        "by exploding the king"
+    }
+
+    //a lone king can not be exploded, but any other piece can explode the king it stands next to
+    fn material_can_draw(&self, lm: &LogicManager) -> bool {
+        [Color::White, Color::Black].iter().any(|color| {
+            lm.get_board().iter().flatten().flatten().all(|piece| piece.color() != *color || piece.piece_type() == PieceTypes::King)
+        })
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
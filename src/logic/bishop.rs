@@ -1,4 +1,4 @@
-use super::piece::{Piece, Board, is_valid_pos, PieceTypes};
+use super::piece::{Piece, Board, is_valid_pos, PieceTypes, Color};
 use colored::*;
 use super::King;
 use std::any::Any;
@@ -6,24 +6,24 @@ use std::any::Any;
 #[derive(Clone)]
 pub struct Bishop {
     pos: (i8, i8),
-    color: bool, //true is white, false is black
+    color: Color,
     first_move: bool,
 }
 
 impl Piece for Bishop {
-    fn new(pos: (i8, i8), color: bool) -> Option<Box<dyn Piece>> {
+    fn new(pos: (i8, i8), color: Color) -> Option<Box<dyn Piece>> {
         Some(Box::new(Bishop { pos, color, first_move: true }))
     }
 
     fn print(&self) {
-        if self.color {
+        if self.color == Color::White {
             print!("{}", "b".bright_white());
         } else {
             print!("{}", "b".black());
         }
     }
 
-    fn color(&self) -> bool {
+    fn color(&self) -> Color {
         self.color
     }
 
@@ -32,18 +32,19 @@ impl Piece for Bishop {
         self.first_move = false;
     }
 
-    fn get_possible_moves(&self, board: &Board, en_passant: &Option<(i8, i8)>, king_pos: (i8, i8), already_called: bool) -> Vec<(i8, i8)> {
+    fn get_possible_moves(&self, board: &Board, _en_passant: &Option<(i8, i8)>, king_pos: (i8, i8), already_called: bool) -> Vec<(i8, i8)> {
         let mut possible_moves = vec![];
         let king = board[king_pos.0 as usize][king_pos.1 as usize].as_ref().unwrap().as_any().downcast_ref::<King>().unwrap();
+        let position = King::check_position(board, already_called);
         for dir in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
             let mut to_check = (self.pos.0 + dir.0, self.pos.1 + dir.1);
             while is_valid_pos(to_check) {
                 if let Some(piece) = board[to_check.0 as usize][to_check.1 as usize].as_ref() {
-                    if piece.color() != self.color && (already_called || !king.is_check(&board, self.pos, to_check, en_passant, king_pos)) {
+                    if piece.color() != self.color && (already_called || !king.is_check(&position, self.pos, to_check, king_pos)) {
                         possible_moves.push(to_check);
                     }
                     break;
-                } else if already_called || !king.is_check(&board, self.pos, to_check, en_passant, king_pos){
+                } else if already_called || !king.is_check(&position, self.pos, to_check, king_pos){
                     possible_moves.push(to_check);
                 }
                 to_check = (to_check.0 + dir.0, to_check.1 + dir.1);
@@ -0,0 +1,212 @@
+use super::piece::{Board, PieceTypes, Color};
+
+//squares are numbered like the board, square = row * 8 + col, so bit 0 is a8 and bit 63 is h1
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+const KING_OFFSETS: [(i32, i32); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+//the first four directions are the rook's and the last four the bishop's
+const DIRECTIONS: [(i32, i32); 8] = [(-1, 0), (1, 0), (0, 1), (0, -1), (-1, 1), (-1, -1), (1, 1), (1, -1)];
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(&KNIGHT_OFFSETS);
+const KING_ATTACKS: [u64; 64] = leaper_attacks(&KING_OFFSETS);
+//indexed by the color of the pawn, black is 0 and white is 1
+const PAWN_ATTACKS: [[u64; 64]; 2] = [leaper_attacks(&[(1, -1), (1, 1)]), leaper_attacks(&[(-1, -1), (-1, 1)])];
+const RAYS: [[u64; 64]; 8] = rays();
+
+const fn leaper_attacks(offsets: &[(i32, i32)]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut sqr = 0;
+    while sqr < 64 {
+        let mut i = 0;
+        while i < offsets.len() {
+            let row = sqr as i32 / 8 + offsets[i].0;
+            let col = sqr as i32 % 8 + offsets[i].1;
+            if row >= 0 && row < 8 && col >= 0 && col < 8 {
+                table[sqr] |= 1 << (row * 8 + col);
+            }
+            i += 1;
+        }
+        sqr += 1;
+    }
+    table
+}
+
+//every square reachable from each square in each direction on an empty board
+const fn rays() -> [[u64; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut sqr = 0;
+        while sqr < 64 {
+            let mut row = sqr as i32 / 8 + DIRECTIONS[dir].0;
+            let mut col = sqr as i32 % 8 + DIRECTIONS[dir].1;
+            while row >= 0 && row < 8 && col >= 0 && col < 8 {
+                table[dir][sqr] |= 1 << (row * 8 + col);
+                row += DIRECTIONS[dir].0;
+                col += DIRECTIONS[dir].1;
+            }
+            sqr += 1;
+        }
+        dir += 1;
+    }
+    table
+}
+
+//the squares a slider attacks in one direction, up to and including the first piece in the way
+fn ray_attacks(sqr: usize, dir: usize, occupancy: u64) -> u64 {
+    let ray = RAYS[dir][sqr];
+    let blockers = ray & occupancy;
+    if blockers == 0 {
+        return ray;
+    }
+    //directions going down the board or to the right increase the square number, so the closest blocker is the lowest bit
+    let blocker = if DIRECTIONS[dir].0 > 0 || (DIRECTIONS[dir].0 == 0 && DIRECTIONS[dir].1 > 0) {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+    ray ^ RAYS[dir][blocker as usize]
+}
+
+pub fn knight_attacks(sqr: usize) -> u64 {
+    KNIGHT_ATTACKS[sqr]
+}
+
+pub fn king_attacks(sqr: usize) -> u64 {
+    KING_ATTACKS[sqr]
+}
+
+//the squares a pawn of color attacks diagonally
+pub fn pawn_attacks(sqr: usize, color: Color) -> u64 {
+    PAWN_ATTACKS[color as usize][sqr]
+}
+
+pub fn rook_attacks(sqr: usize, occupancy: u64) -> u64 {
+    (0..4).fold(0, |attacks, dir| attacks | ray_attacks(sqr, dir, occupancy))
+}
+
+pub fn bishop_attacks(sqr: usize, occupancy: u64) -> u64 {
+    (4..8).fold(0, |attacks, dir| attacks | ray_attacks(sqr, dir, occupancy))
+}
+
+pub fn square(pos: (i8, i8)) -> usize {
+    (pos.0 * 8 + pos.1) as usize
+}
+
+//the position as one 64 bit set of squares for every piece type of every color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bitboards {
+    pieces: [[u64; 6]; 2], //indexed by color, black is 0 and white is 1, and then by PieceTypes
+    occupancy: [u64; 2],
+}
+
+impl Bitboards {
+    pub fn from_board(board: &Board) -> Bitboards {
+        let mut res = Bitboards::default();
+        for (i, row) in board.iter().enumerate() {
+            for (j, sqr) in row.iter().enumerate() {
+                if let Some(piece) = sqr {
+                    res.put(square((i as i8, j as i8)), piece.piece_type(), piece.color());
+                }
+            }
+        }
+        res
+    }
+
+    //the squares holding pieces of the given type and color
+    pub fn pieces(&self, piece_type: PieceTypes, color: Color) -> u64 {
+        self.pieces[color as usize][piece_type as usize]
+    }
+
+    //the squares holding pieces of color
+    pub fn color_occupancy(&self, color: Color) -> u64 {
+        self.occupancy[color as usize]
+    }
+
+    pub fn occupancy(&self) -> u64 {
+        self.occupancy[0] | self.occupancy[1]
+    }
+
+    //the type and color of the piece on sqr, if any
+    pub fn piece_at(&self, sqr: usize) -> Option<(PieceTypes, Color)> {
+        let bit = 1 << sqr;
+        let color = if self.color_occupancy(Color::White) & bit != 0 {
+            Color::White
+        } else if self.color_occupancy(Color::Black) & bit != 0 {
+            Color::Black
+        } else {
+            return None;
+        };
+        let piece_types = [PieceTypes::King, PieceTypes::Queen, PieceTypes::Rook, PieceTypes::Bishop, PieceTypes::Knight, PieceTypes::Pawn];
+        piece_types.iter().find(|piece_type| self.pieces(**piece_type, color) & bit != 0).map(|piece_type| (*piece_type, color))
+    }
+
+    //true if any piece of color attacks sqr
+    pub fn is_attacked(&self, sqr: usize, color: Color) -> bool {
+        let occupancy = self.occupancy();
+        let rooks = self.pieces(PieceTypes::Rook, color) | self.pieces(PieceTypes::Queen, color);
+        let bishops = self.pieces(PieceTypes::Bishop, color) | self.pieces(PieceTypes::Queen, color);
+        //a pawn of color attacks sqr if a pawn of the other color on sqr would attack it back
+        pawn_attacks(sqr, color.opposite()) & self.pieces(PieceTypes::Pawn, color) != 0 ||
+            knight_attacks(sqr) & self.pieces(PieceTypes::Knight, color) != 0 ||
+            king_attacks(sqr) & self.pieces(PieceTypes::King, color) != 0 ||
+            rook_attacks(sqr, occupancy) & rooks != 0 ||
+            bishop_attacks(sqr, occupancy) & bishops != 0
+    }
+
+    //moves the piece on from to to, capturing whatever is there. A pawn moving diagonally to an empty square captures en passant
+    pub fn move_piece(&mut self, from: (i8, i8), to: (i8, i8)) {
+        let (piece_type, color) = match self.piece_at(square(from)) {
+            Some(piece) => piece,
+            None => return,
+        };
+        let is_en_passant = piece_type == PieceTypes::Pawn && from.1 != to.1 && self.piece_at(square(to)).is_none();
+        if let Some((captured_type, captured_color)) = self.piece_at(square(to)) {
+            self.remove(square(to), captured_type, captured_color);
+        }
+        if is_en_passant {
+            self.remove(square((from.0, to.1)), PieceTypes::Pawn, color.opposite());
+        }
+        self.remove(square(from), piece_type, color);
+        self.put(square(to), piece_type, color);
+    }
+
+    //moves the king and the rook it castles with, either of which may land on the square the other left
+    pub fn castle(&mut self, king_from: (i8, i8), king_to: (i8, i8), rook_from: (i8, i8), rook_to: (i8, i8)) {
+        let color = match self.piece_at(square(king_from)) {
+            Some((_, color)) => color,
+            None => return,
+        };
+        self.remove(square(king_from), PieceTypes::King, color);
+        self.remove(square(rook_from), PieceTypes::Rook, color);
+        self.put(square(king_to), PieceTypes::King, color);
+        self.put(square(rook_to), PieceTypes::Rook, color);
+    }
+
+    //removes the piece on center and every piece but the pawns around it, like a capture on center in atomic chess
+    pub fn explode(&mut self, center: (i8, i8)) {
+        let center = square(center);
+        let mut blast = king_attacks(center) & !(self.pieces(PieceTypes::Pawn, Color::White) | self.pieces(PieceTypes::Pawn, Color::Black));
+        blast |= 1 << center;
+        for color in [Color::White, Color::Black] {
+            for pieces in self.pieces[color as usize].iter_mut() {
+                *pieces &= !blast;
+            }
+            self.occupancy[color as usize] &= !blast;
+        }
+    }
+
+    //puts a piece on the empty square pos, like a piece dropped from the hand of a player
+    pub fn put_piece(&mut self, pos: (i8, i8), piece_type: PieceTypes, color: Color) {
+        self.put(square(pos), piece_type, color);
+    }
+
+    fn put(&mut self, sqr: usize, piece_type: PieceTypes, color: Color) {
+        self.pieces[color as usize][piece_type as usize] |= 1 << sqr;
+        self.occupancy[color as usize] |= 1 << sqr;
+    }
+
+    fn remove(&mut self, sqr: usize, piece_type: PieceTypes, color: Color) {
+        self.pieces[color as usize][piece_type as usize] &= !(1 << sqr);
+        self.occupancy[color as usize] &= !(1 << sqr);
+    }
+}
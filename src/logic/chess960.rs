@@ -0,0 +1,57 @@
+use super::piece::PieceTypes;
+use super::fen::fen_char_case;
+use super::san::piece_letter;
+use super::{LogicManager, Color};
+
+//where the knights go among the five squares left after placing the bishops and the queen, indexed as in the standard numbering
+const KNIGHT_SQUARES: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+//the back rank of the Chess960 start position with the given number, from the a file to the h file.
+//Numbers go from 0 to 959, 518 is the standard setup. Returns None for any other number
+pub fn chess960_back_rank(number: u16) -> Option<[PieceTypes; 8]> {
+    if number >= 960 {
+        return None;
+    }
+    let mut rank = [None; 8];
+    let mut n = number as usize;
+    //the bishops stand on squares of different colors, the first on b, d, f or h and the second on a, c, e or g
+    rank[n % 4 * 2 + 1] = Some(PieceTypes::Bishop);
+    n /= 4;
+    rank[n % 4 * 2] = Some(PieceTypes::Bishop);
+    n /= 4;
+    place(&mut rank, n % 6, PieceTypes::Queen);
+    n /= 6;
+    let (first, second) = KNIGHT_SQUARES[n];
+    //placing the second knight first keeps the index of the first one the same
+    place(&mut rank, second, PieceTypes::Knight);
+    place(&mut rank, first, PieceTypes::Knight);
+    //the king always stands between the rooks
+    for piece_type in [PieceTypes::Rook, PieceTypes::King, PieceTypes::Rook] {
+        place(&mut rank, 0, piece_type);
+    }
+    let mut res = [PieceTypes::Pawn; 8];
+    for (sqr, piece_type) in res.iter_mut().zip(rank.iter()) {
+        *sqr = piece_type.unwrap();
+    }
+    Some(res)
+}
+
+//the Chess960 start position with the given number in Forsyth-Edwards Notation, see chess960_back_rank
+pub fn chess960_fen(number: u16) -> Option<String> {
+    let rank = chess960_back_rank(number)?;
+    let pieces = |color: Color| rank.iter().map(|piece_type| fen_char_case(piece_letter(*piece_type).to_ascii_lowercase(), color)).collect::<String>();
+    Some(format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1", pieces(Color::Black), pieces(Color::White)))
+}
+
+//puts piece_type on the empty square with the given index, counting only empty squares from the a file
+fn place(rank: &mut [Option<PieceTypes>; 8], index: usize, piece_type: PieceTypes) {
+    let sqr = rank.iter_mut().filter(|sqr| sqr.is_none()).nth(index).unwrap();
+    *sqr = Some(piece_type);
+}
+
+impl LogicManager {
+    //starts a Chess960 game from the start position with the given number, see chess960_back_rank
+    pub fn new_chess960(number: u16) -> Option<LogicManager> {
+        LogicManager::from_fen_chess960(&chess960_fen(number)?).ok()
+    }
+}
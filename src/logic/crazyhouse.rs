@@ -0,0 +1,178 @@
+use super::piece::{PieceTypes, Color};
+use super::bitboard::square;
+use super::fen::fen_char_case;
+use super::san::{piece_letter, letter_piece};
+use super::zobrist::variant_key;
+use super::variant::Variant;
+use super::{LogicManager, Move, MoveKind, Square, FenError};
+use std::any::Any;
+
+//the pieces that can be held in hand, in the order pockets list them
+const POCKET_PIECES: [PieceTypes; 5] = [PieceTypes::Pawn, PieceTypes::Knight, PieceTypes::Bishop, PieceTypes::Rook, PieceTypes::Queen];
+
+//captured pieces go into the pocket of the player who took them, and can be dropped back on the board as their own
+//pieces instead of moving. Promoted pieces go back into the pocket as pawns
+#[derive(Clone, Default)]
+pub struct Crazyhouse {
+    pockets: [[u8; 5]; 2], //indexed by color, black is 0 and white is 1, then like POCKET_PIECES
+    promoted: u64, //the squares of the pieces that were promoted from pawns, numbered like the bitboards
+}
+
+impl Crazyhouse {
+    //how many pieces of piece_type color holds
+    pub fn held(&self, color: Color, piece_type: PieceTypes) -> u8 {
+        pocket_index(piece_type).map_or(0, |i| self.pockets[color as usize][i])
+    }
+
+    //true if the piece on pos was promoted from a pawn
+    pub fn is_promoted(&self, pos: (i8, i8)) -> bool {
+        self.promoted & 1 << square(pos) != 0
+    }
+}
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1".to_string()
+    }
+
+    fn after_move(&mut self, lm: &mut LogicManager, mv: Move, captured: Option<(Square, PieceTypes)>) {
+        let color = lm.side_to_move() as usize;
+        if let MoveKind::Drop(piece_type) = mv.kind {
+            if let Some(i) = pocket_index(piece_type) {
+                self.pockets[color][i] -= 1;
+            }
+            return;
+        }
+        if let Some((sqr, piece_type)) = captured {
+            let piece_type = if self.is_promoted(sqr.pos()) { PieceTypes::Pawn } else { piece_type };
+            self.promoted &= !(1 << square(sqr.pos()));
+            if let Some(i) = pocket_index(piece_type) {
+                self.pockets[color][i] += 1;
+            }
+        }
+        if self.is_promoted(mv.from.pos()) || mv.promotion.is_some() {
+            self.promoted &= !(1 << square(mv.from.pos()));
+            self.promoted |= 1 << square(mv.destination().pos());
+        }
+    }
+
+    //pieces in hand can always be dropped to checkmate
+    fn material_can_draw(&self, _lm: &LogicManager) -> bool {
+        self.pockets.iter().flatten().all(|count| *count == 0)
+    }
+
+    fn pocket(&self, color: Color) -> Option<Vec<(PieceTypes, u8)>> {
+        Some(POCKET_PIECES.iter().map(|piece_type| (*piece_type, self.held(color, *piece_type))).collect())
+    }
+
+    //the pockets follow the piece placement, either in brackets ("RNBQKBNR[Qn]") or as a ninth rank ("RNBQKBNR/Qn"),
+    //and a promoted piece is followed by a tilde ("Q~")
+    fn read_fen(&mut self, fen: &str) -> Result<String, FenError> {
+        let (placement, rest) = fen.trim_start().split_once(' ').unwrap_or((fen.trim_start(), ""));
+        let (placement, pocket) = if let Some((placement, pocket)) = placement.split_once('[') {
+            match pocket.strip_suffix(']') {
+                Some(pocket) => (placement, pocket),
+                None => return Err(FenError::Variant(format!("the pockets '[{}' are not closed", pocket))),
+            }
+        } else if placement.matches('/').count() == 8 {
+            placement.rsplit_once('/').unwrap()
+        } else {
+            (placement, "")
+        };
+
+        *self = Crazyhouse::default();
+        for c in pocket.chars().filter(|c| *c != '-') {
+            let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+            let piece_type = match c.to_ascii_uppercase() {
+                'P' => Some(PieceTypes::Pawn),
+                letter => letter_piece(letter),
+            };
+            match piece_type.and_then(pocket_index) {
+                Some(i) => self.pockets[color as usize][i] += 1,
+                None => return Err(FenError::Variant(format!("'{}' can not be held in a pocket", c))),
+            }
+        }
+
+        let mut standard = String::new();
+        let (mut row, mut col) = (0, 0);
+        for c in placement.chars() {
+            match c {
+                '~' if col > 0 => self.promoted |= 1 << square((row, col - 1)),
+                '~' => return Err(FenError::Variant("'~' does not follow a piece".to_string())),
+                '/' => {
+                    row += 1;
+                    col = 0;
+                }
+                _ => col += c.to_digit(10).map_or(1, |n| n as i8),
+            }
+            if c != '~' {
+                standard.push(c);
+            }
+        }
+        Ok(format!("{} {}", standard, rest))
+    }
+
+    fn write_fen(&self, fen: String) -> String {
+        let (placement, rest) = fen.split_once(' ').unwrap_or((&fen, ""));
+        let mut res = String::new();
+        let (mut row, mut col) = (0, 0);
+        for c in placement.chars() {
+            res.push(c);
+            match c {
+                '/' => {
+                    row += 1;
+                    col = 0;
+                }
+                _ => {
+                    let n = c.to_digit(10).map_or(1, |n| n as i8);
+                    if n == 1 && c.is_ascii_alphabetic() && self.is_promoted((row, col)) {
+                        res.push('~');
+                    }
+                    col += n;
+                }
+            }
+        }
+        res.push('[');
+        for color in [Color::White, Color::Black] {
+            for piece_type in POCKET_PIECES.iter().rev() {
+                for _ in 0..self.held(color, *piece_type) {
+                    res.push(fen_char_case(piece_letter(*piece_type).to_ascii_lowercase(), color));
+                }
+            }
+        }
+        format!("{}] {}", res, rest)
+    }
+
+    fn hash(&self) -> u64 {
+        let mut hash = 0;
+        for (color, pocket) in self.pockets.iter().enumerate() {
+            for (i, count) in pocket.iter().enumerate() {
+                if *count > 0 {
+                    hash ^= variant_key(((color * 5 + i) * 64 + *count as usize) as u64);
+                }
+            }
+        }
+        for sqr in 0..64 {
+            if self.promoted & 1 << sqr != 0 {
+                hash ^= variant_key(1000 + sqr);
+            }
+        }
+        hash
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn pocket_index(piece_type: PieceTypes) -> Option<usize> {
+    POCKET_PIECES.iter().position(|held| *held == piece_type)
+}
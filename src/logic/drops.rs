@@ -0,0 +1,102 @@
+use super::piece::{PieceTypes, Color, square_name};
+use super::bitboard::{Bitboards, square};
+use super::san::piece_letter;
+use super::{LogicManager, Move, MoveOutcome, new_piece};
+
+impl LogicManager {
+    //the squares the side to move can drop a piece of piece_type on, empty if it holds no such piece
+    pub fn drop_squares(&self, piece_type: PieceTypes) -> Vec<(i8, i8)> {
+        self.drop_squares_of(self.turn, piece_type)
+    }
+
+    //drops a piece of piece_type from the hand of the side to move on pos. Returns None without changing anything
+    //if that is not a legal drop
+    pub fn drop_piece(&mut self, piece_type: PieceTypes, pos: (i8, i8)) -> Option<MoveOutcome> {
+        if self.stop || !self.drop_squares(piece_type).contains(&pos) {
+            return None;
+        }
+        let mv = Move::piece_drop(piece_type, pos.into());
+        self.clear_selection();
+        let san = self.begin_move(mv);
+        self.hash ^= self.state_hash();
+        let mut piece = new_piece(piece_type, pos, self.turn).unwrap();
+        //a pawn dropped on its starting rank may still move two squares, no other dropped piece can castle
+        let start_row = if self.turn == Color::White { 6 } else { 1 };
+        if piece_type != PieceTypes::Pawn || pos.0 != start_row {
+            piece.move_piece(pos);
+        }
+        self.board[pos.0 as usize][pos.1 as usize] = Some(piece);
+        self.toggle_piece_hash(pos);
+        self.en_passant = None;
+        if piece_type == PieceTypes::Pawn {
+            self.halfmove_clock = 0;
+            self.clear_past_positions();
+        } else {
+            self.halfmove_clock += 1;
+        }
+        self.variant_after_move(mv, None);
+        self.end_turn();
+        self.hash ^= self.state_hash();
+        self.record_position();
+        self.finish_move(mv, san);
+        Some(self.outcome(mv, piece_type, None, None))
+    }
+
+    //every legal drop of the side to move
+    pub(crate) fn drop_moves(&self) -> Vec<Move> {
+        let pocket = match self.variant.pocket(self.turn) {
+            Some(pocket) => pocket,
+            None => return vec![],
+        };
+        let mut moves = vec![];
+        for (piece_type, count) in pocket {
+            if count > 0 {
+                moves.extend(self.drop_squares(piece_type).into_iter().map(|pos| Move::piece_drop(piece_type, pos.into())));
+            }
+        }
+        moves
+    }
+
+    //true if color has a legal drop
+    pub(crate) fn can_drop(&self, color: Color) -> bool {
+        match self.variant.pocket(color) {
+            Some(pocket) => pocket.iter().any(|(piece_type, count)| *count > 0 && !self.drop_squares_of(color, *piece_type).is_empty()),
+            None => false,
+        }
+    }
+
+    //the empty squares color can drop a piece of piece_type on. Pawns can not be dropped on the first or last rank,
+    //and while in check only drops blocking it are legal
+    fn drop_squares_of(&self, color: Color, piece_type: PieceTypes) -> Vec<(i8, i8)> {
+        let holds = self.variant.pocket(color)
+            .is_some_and(|pocket| pocket.iter().any(|(held, count)| *held == piece_type && *count > 0));
+        if !holds || piece_type == PieceTypes::King {
+            return vec![];
+        }
+        let bitboards = Bitboards::from_board(&self.board);
+        let king = square(self.king_pos(color));
+        let in_check = bitboards.is_attacked(king, color.opposite());
+        let mut res = vec![];
+        for i in 0..8 {
+            for j in 0..8 {
+                if self.board[i as usize][j as usize].is_some() || (piece_type == PieceTypes::Pawn && (i == 0 || i == 7)) {
+                    continue;
+                }
+                if in_check {
+                    let mut after = bitboards;
+                    after.put_piece((i, j), piece_type, color);
+                    if after.is_attacked(king, color.opposite()) {
+                        continue;
+                    }
+                }
+                res.push((i, j));
+            }
+        }
+        res
+    }
+}
+
+//the notation of a drop, like "N@f3" or "P@e4"
+pub(crate) fn drop_san(piece_type: PieceTypes, pos: (i8, i8)) -> String {
+    format!("{}@{}", piece_letter(piece_type), square_name(pos))
+}
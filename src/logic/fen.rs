@@ -0,0 +1,362 @@
+use super::piece::{Piece, Board, PieceTypes, Color, square_name, parse_square};
+use super::{LogicManager, Pawn, Knight, Rook, Bishop, Queen, King};
+use super::variant::{Variant, Standard};
+use std::fmt;
+use std::collections::HashMap;
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+//describes which field of a FEN string could not be parsed, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    FieldCount(usize),
+    PiecePlacement(String),
+    SideToMove(String),
+    Castling(String),
+    EnPassant(String),
+    HalfmoveClock(String),
+    FullmoveNumber(String),
+    Variant(String), //the state of the variant the FEN carries besides the standard fields
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::FieldCount(n) => write!(f, "expected 4 to 6 space separated fields, found {}", n),
+            FenError::PiecePlacement(msg) => write!(f, "invalid piece placement: {}", msg),
+            FenError::SideToMove(msg) => write!(f, "invalid side to move: {}", msg),
+            FenError::Castling(msg) => write!(f, "invalid castling rights: {}", msg),
+            FenError::EnPassant(msg) => write!(f, "invalid en passant square: {}", msg),
+            FenError::HalfmoveClock(msg) => write!(f, "invalid halfmove clock: {}", msg),
+            FenError::FullmoveNumber(msg) => write!(f, "invalid fullmove number: {}", msg),
+            FenError::Variant(msg) => write!(f, "invalid state of the variant: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl LogicManager {
+    //creates a LogicManager from a position in Forsyth-Edwards Notation. The halfmove clock and fullmove number may be omitted
+    pub fn from_fen(fen: &str) -> Result<LogicManager, FenError> {
+        LogicManager::parse_fen(fen, false, Box::new(Standard))
+    }
+
+    //creates a Chess960 game from a position in Forsyth-Edwards Notation. The castling rights may be given as in
+    //Shredder-FEN ("HAha") or X-FEN ("KQkq")
+    pub fn from_fen_chess960(fen: &str) -> Result<LogicManager, FenError> {
+        LogicManager::parse_fen(fen, true, Box::new(Standard))
+    }
+
+    //creates a game of variant from a FEN, which the variant reads its own state from first
+    pub(crate) fn parse_fen(fen: &str, chess960: bool, mut variant: Box<dyn Variant>) -> Result<LogicManager, FenError> {
+        let fen = variant.read_fen(fen)?;
+        let fields = fen.split_whitespace().collect::<Vec<_>>();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(FenError::FieldCount(fields.len()));
+        }
+
+        let mut board = parse_placement(fields[0])?;
+        let white_king = find_king(&board, Color::White)?;
+        let black_king = find_king(&board, Color::Black)?;
+
+        let turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            other => return Err(FenError::SideToMove(format!("expected 'w' or 'b', found '{}'", other))),
+        };
+
+        apply_castling(&mut board, fields[2], chess960)?;
+        let en_passant = apply_en_passant(&mut board, fields[3], turn)?;
+
+        let halfmove_clock = match fields.get(4) {
+            Some(field) => field.parse::<u16>()
+                .map_err(|_| FenError::HalfmoveClock(format!("'{}' is not a non-negative number", field)))?,
+            None => 0,
+        };
+        let fullmove_number = match fields.get(5) {
+            Some(field) => match field.parse::<u16>() {
+                Ok(n) if n > 0 => n,
+                _ => return Err(FenError::FullmoveNumber(format!("'{}' is not a positive number", field))),
+            },
+            None => 1,
+        };
+
+        let mut res = LogicManager {
+            board,
+            curr_selected: (-1, -1),
+            possible_moves: vec![],
+            en_passant,
+            black_king,
+            white_king,
+            hash: 0,
+            past_positions: HashMap::new(),
+            halfmove_clock,
+            claimed_draw: None,
+            turn,
+            fullmove_number,
+            chess960,
+            variant,
+            removed: vec![],
+            start_fen: String::new(),
+            moves: vec![],
+            san_moves: vec![],
+            history: vec![],
+            future: vec![],
+            stop: false,
+            recording: true,
+            taken: vec![],
+        };
+        res.hash = res.compute_hash();
+        res.record_position();
+        res.start_fen = res.to_fen();
+        Ok(res)
+    }
+
+    //returns the current position in Forsyth-Edwards Notation. The castling rights of Chess960 games are written as in X-FEN
+    pub fn to_fen(&self) -> String {
+        self.fen(false)
+    }
+
+    //returns the current position in Forsyth-Edwards Notation with the castling rights written as in Shredder-FEN,
+    //as the files of the rooks
+    pub fn to_shredder_fen(&self) -> String {
+        self.fen(true)
+    }
+
+    fn fen(&self, shredder: bool) -> String {
+        let mut placement = String::new();
+        for (i, row) in self.board.iter().enumerate() {
+            let mut empty = 0;
+            for sqr in row {
+                if let Some(piece) = sqr {
+                    if empty > 0 {
+                        placement.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    placement.push(piece_char(piece.as_ref()));
+                } else {
+                    empty += 1;
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if i != 7 {
+                placement.push('/');
+            }
+        }
+
+        let castling = self.castling_field(shredder);
+
+        let en_passant = match self.en_passant {
+            Some(pos) => square_name(pos),
+            None => "-".to_string(),
+        };
+
+        let fen = format!("{} {} {} {} {} {}", placement, if self.turn == Color::White { "w" } else { "b" },
+                castling, en_passant, self.halfmove_clock, self.fullmove_number);
+        self.variant.write_fen(fen)
+    }
+
+    //the castling rights, written as K or Q unless another rook stands further out on the same side (X-FEN), or always
+    //as the file of the rook with shredder (Shredder-FEN)
+    fn castling_field(&self, shredder: bool) -> String {
+        let mut castling = String::new();
+        for color in [Color::White, Color::Black] {
+            let row = self.king_pos(color).0 as usize;
+            for kingside in [true, false] {
+                let rook_col = match self.castling_rook_col(color, kingside) {
+                    Some(col) => col,
+                    None => continue,
+                };
+                let mut further_out = if kingside { rook_col + 1..8 } else { 0..rook_col };
+                let outermost = further_out.all(|col| !self.board[row][col as usize].as_ref()
+                    .is_some_and(|piece| piece.piece_type() == PieceTypes::Rook && piece.color() == color));
+                let c = if shredder || !outermost {
+                    (b'a' + rook_col as u8) as char
+                } else if kingside {
+                    'k'
+                } else {
+                    'q'
+                };
+                castling.push(fen_char_case(c, color));
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        castling
+    }
+}
+
+fn parse_placement(field: &str) -> Result<Board, FenError> {
+    let rows = field.split('/').collect::<Vec<_>>();
+    if rows.len() != 8 {
+        return Err(FenError::PiecePlacement(format!("expected 8 ranks, found {}", rows.len())));
+    }
+    let mut board = Vec::with_capacity(8);
+    for (i, row_str) in rows.iter().enumerate() {
+        let mut row: Vec<Option<Box<dyn Piece>>> = Vec::with_capacity(8);
+        for c in row_str.chars() {
+            if let Some(n) = c.to_digit(10) {
+                if n == 0 || n > 8 {
+                    return Err(FenError::PiecePlacement(format!("invalid empty square count '{}' on rank {}", c, 8 - i)));
+                }
+                for _ in 0..n {
+                    row.push(None);
+                }
+                continue;
+            }
+            let pos = (i as i8, row.len() as i8);
+            let piece = create_piece(c, pos)
+                .ok_or_else(|| FenError::PiecePlacement(format!("unknown piece '{}' on rank {}", c, 8 - i)))?;
+            if piece.piece_type() == PieceTypes::Pawn && (i == 0 || i == 7) {
+                return Err(FenError::PiecePlacement(format!("pawn on rank {}", 8 - i)));
+            }
+            row.push(Some(piece));
+        }
+        if row.len() != 8 {
+            return Err(FenError::PiecePlacement(format!("rank {} describes {} squares instead of 8", 8 - i, row.len())));
+        }
+        board.push(row);
+    }
+
+    //pawns that left their starting rank can no longer move two squares
+    for (i, row) in board.iter_mut().enumerate() {
+        for (j, sqr) in row.iter_mut().enumerate() {
+            if let Some(piece) = sqr {
+                let start_row = if piece.color() == Color::White { 6 } else { 1 };
+                if piece.piece_type() != PieceTypes::Pawn || i != start_row {
+                    piece.move_piece((i as i8, j as i8));
+                }
+            }
+        }
+    }
+    Ok(board)
+}
+
+fn find_king(board: &Board, color: Color) -> Result<(i8, i8), FenError> {
+    let mut kings = vec![];
+    for (i, row) in board.iter().enumerate() {
+        for (j, sqr) in row.iter().enumerate() {
+            if let Some(piece) = sqr {
+                if piece.piece_type() == PieceTypes::King && piece.color() == color {
+                    kings.push((i as i8, j as i8));
+                }
+            }
+        }
+    }
+    let name = if color == Color::White { "white" } else { "black" };
+    match kings.len() {
+        1 => Ok(kings[0]),
+        n => Err(FenError::PiecePlacement(format!("expected exactly one {} king, found {}", name, n))),
+    }
+}
+
+//marks the kings and rooks that are still allowed to castle as not having moved. In Chess960 a right may name the file
+//of its rook (Shredder-FEN), and K or Q stand for the outermost rook on that side of the king (X-FEN)
+fn apply_castling(board: &mut Board, field: &str, chess960: bool) -> Result<(), FenError> {
+    if field == "-" {
+        return Ok(());
+    }
+    let mut seen = vec![];
+    for c in field.chars() {
+        let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+        let row = if color == Color::White { 7 } else { 0 };
+        let is_own = |col: i8, piece_type: PieceTypes| board[row][col as usize].as_ref()
+            .is_some_and(|piece| piece.piece_type() == piece_type && piece.color() == color);
+        let king_col = match (0..8).find(|col| is_own(*col, PieceTypes::King)) {
+            Some(col) if chess960 || col == 4 => col,
+            _ if chess960 => return Err(FenError::Castling(format!("'{}' requires a king on rank {}", c, 8 - row))),
+            _ => return Err(FenError::Castling(format!("'{}' requires a king on {}", c, square_name((row as i8, 4))))),
+        };
+        let letter = c.to_ascii_lowercase();
+        let rook_col = match letter {
+            'k' if chess960 => (king_col + 1..8).rev().find(|col| is_own(*col, PieceTypes::Rook)),
+            'q' if chess960 => (0..king_col).find(|col| is_own(*col, PieceTypes::Rook)),
+            'k' => Some(7).filter(|col| is_own(*col, PieceTypes::Rook)),
+            'q' => Some(0).filter(|col| is_own(*col, PieceTypes::Rook)),
+            file @ 'a'..='h' if chess960 => Some(file as i8 - 'a' as i8).filter(|col| *col != king_col && is_own(*col, PieceTypes::Rook)),
+            _ => return Err(FenError::Castling(format!("unexpected character '{}'", c))),
+        };
+        let rook_col = match rook_col {
+            Some(col) => col,
+            None if chess960 => return Err(FenError::Castling(format!("'{}' requires a rook on rank {} on that side of the king", c, 8 - row))),
+            None => return Err(FenError::Castling(format!("'{}' requires a rook on {}", c, square_name((row as i8, if letter == 'k' { 7 } else { 0 }))))),
+        };
+        let side = (color, rook_col > king_col);
+        if seen.contains(&side) {
+            return Err(FenError::Castling(format!("'{}' repeats a right to castle to the same side", c)));
+        }
+        seen.push(side);
+
+        for (col, piece_type) in [(king_col, PieceTypes::King), (rook_col, PieceTypes::Rook)] {
+            board[row][col as usize] = create_piece(fen_char(piece_type, color), (row as i8, col));
+        }
+    }
+    Ok(())
+}
+
+//validates the en passant square and marks the pawn that just moved two squares
+fn apply_en_passant(board: &mut Board, field: &str, turn: Color) -> Result<Option<(i8, i8)>, FenError> {
+    if field == "-" {
+        return Ok(None);
+    }
+    let pos = parse_square(field)
+        .ok_or_else(|| FenError::EnPassant(format!("'{}' is not a square", field)))?;
+    let (expected_row, dir) = if turn == Color::White { (2, 1) } else { (5, -1) };
+    if pos.0 != expected_row {
+        return Err(FenError::EnPassant(format!("{} is not on rank {}", field, 8 - expected_row)));
+    }
+    let pawn_pos = (pos.0 + dir, pos.1);
+    let origin = (pos.0 - dir, pos.1);
+    match board[pawn_pos.0 as usize][pawn_pos.1 as usize].as_ref() {
+        Some(piece) if piece.piece_type() == PieceTypes::Pawn && piece.color() != turn => (),
+        _ => return Err(FenError::EnPassant(format!("no pawn on {} that could have just moved two squares", square_name(pawn_pos)))),
+    }
+    if board[pos.0 as usize][pos.1 as usize].is_some() || board[origin.0 as usize][origin.1 as usize].is_some() {
+        return Err(FenError::EnPassant(format!("the squares behind {} are not empty", square_name(pawn_pos))));
+    }
+    let mut pawn = Pawn::new(origin, turn.opposite()).unwrap();
+    pawn.move_piece(pawn_pos);
+    board[pawn_pos.0 as usize][pawn_pos.1 as usize] = Some(pawn);
+    Ok(Some(pos))
+}
+
+fn create_piece(c: char, pos: (i8, i8)) -> Option<Box<dyn Piece>> {
+    let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+    match c.to_ascii_lowercase() {
+        'k' => King::new(pos, color),
+        'q' => Queen::new(pos, color),
+        'r' => Rook::new(pos, color),
+        'b' => Bishop::new(pos, color),
+        'n' => Knight::new(pos, color),
+        'p' => Pawn::new(pos, color),
+        _ => None,
+    }
+}
+
+fn fen_char(piece_type: PieceTypes, color: Color) -> char {
+    let c = match piece_type {
+        PieceTypes::King => 'k',
+        PieceTypes::Queen => 'q',
+        PieceTypes::Rook => 'r',
+        PieceTypes::Bishop => 'b',
+        PieceTypes::Knight => 'n',
+        PieceTypes::Pawn => 'p',
+    };
+    fen_char_case(c, color)
+}
+
+//white pieces are written in upper case and black pieces in lower case
+pub(crate) fn fen_char_case(c: char, color: Color) -> char {
+    match color {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c,
+    }
+}
+
+pub(crate) fn piece_char(piece: &dyn Piece) -> char {
+    fen_char(piece.piece_type(), piece.color())
+}
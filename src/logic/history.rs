@@ -0,0 +1,214 @@
+use super::piece::{Board, Color, Piece};
+use super::{LogicManager, GameStatus, Move, MoveKind};
+use super::variant::Variant;
+use super::king::castling_squares;
+use std::collections::HashMap;
+
+//everything needed to restore the game to the position before a move
+#[derive(Clone)]
+pub(crate) struct Snapshot {
+    board: Board,
+    en_passant: Option<(i8, i8)>,
+    black_king: (i8, i8),
+    white_king: (i8, i8),
+    hash: u64,
+    past_positions: HashMap<u64, u8>,
+    halfmove_clock: u16,
+    claimed_draw: Option<GameStatus>,
+    turn: Color,
+    fullmove_number: u16,
+    variant: Box<dyn Variant>, //variants may keep state that changes with the moves
+    moves: Vec<Move>,
+    san_moves: Vec<String>,
+}
+
+//a square and what stood on it
+type SavedSquare = ((i8, i8), Option<Box<dyn Piece>>);
+
+//what is needed to take back a move of a search, which unlike a snapshot leaves out the history of the game
+pub(crate) struct SearchUndo {
+    squares: Vec<SavedSquare>, //the squares the move itself changes, as they were before it
+    taken: Vec<((i8, i8), Box<dyn Piece>)>, //the pieces the variant took off the board after the move
+    en_passant: Option<(i8, i8)>,
+    black_king: (i8, i8),
+    white_king: (i8, i8),
+    hash: u64,
+    halfmove_clock: u16,
+    turn: Color,
+    fullmove_number: u16,
+    variant: Box<dyn Variant>,
+}
+
+impl LogicManager {
+    //takes back the last move. Returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        if let Some(snapshot) = self.history.pop() {
+            let current = self.snapshot();
+            self.future.push(current);
+            self.restore(snapshot);
+            true
+        } else {
+            false
+        }
+    }
+
+    //replays the last move that was taken back. Returns false if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        if let Some(snapshot) = self.future.pop() {
+            let current = self.snapshot();
+            self.history.push(current);
+            self.restore(snapshot);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    //the moves that were taken back and can be replayed, the next one redo would play first
+    pub fn get_undone_moves(&self) -> &[Move] {
+        match self.future.first() {
+            Some(furthest) => &furthest.moves[self.moves.len()..],
+            None => &[],
+        }
+    }
+
+    //makes a move of a search such as perft or the engine, which is taken back with unmake_search_move right after.
+    //The move is not recorded and its notation is not computed. Returns None if mv is not legal
+    pub(crate) fn make_search_move(&mut self, mv: Move) -> Option<SearchUndo> {
+        let (from, to) = (mv.from.pos(), mv.to.pos());
+        let mut changed = vec![to];
+        if !matches!(mv.kind, MoveKind::Drop(_)) {
+            //an en passant capture takes the pawn beside from
+            changed.extend([from, (from.0, to.1)]);
+            if let Some(rook) = self.castling_rook_of(from, to) {
+                let (king_col, rook_col) = castling_squares(rook.1 > from.1);
+                changed.extend([rook, (from.0, king_col), (from.0, rook_col)]);
+            }
+        }
+        let mut undo = SearchUndo {
+            squares: changed.into_iter().map(|pos| (pos, self.board[pos.0 as usize][pos.1 as usize].clone())).collect(),
+            taken: vec![],
+            en_passant: self.en_passant,
+            black_king: self.black_king,
+            white_king: self.white_king,
+            hash: self.hash,
+            halfmove_clock: self.halfmove_clock,
+            turn: self.turn,
+            fullmove_number: self.fullmove_number,
+            variant: self.variant.clone(),
+        };
+        self.recording = false;
+        let outcome = self.apply_move(mv);
+        self.recording = true;
+        undo.taken = std::mem::take(&mut self.taken);
+        outcome.map(|_| undo)
+    }
+
+    pub(crate) fn unmake_search_move(&mut self, undo: SearchUndo) {
+        //moves of a search do not clear the earlier positions, only the one after the move has to be forgotten
+        if let Some(count) = self.past_positions.get_mut(&self.hash) {
+            *count -= 1;
+            if *count == 0 {
+                self.past_positions.remove(&self.hash);
+            }
+        }
+        //the squares of the move go back last, since the variant may also have taken the moved piece
+        for (pos, piece) in undo.taken {
+            self.board[pos.0 as usize][pos.1 as usize] = Some(piece);
+        }
+        for (pos, piece) in undo.squares {
+            self.board[pos.0 as usize][pos.1 as usize] = piece;
+        }
+        self.en_passant = undo.en_passant;
+        self.black_king = undo.black_king;
+        self.white_king = undo.white_king;
+        self.hash = undo.hash;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.turn = undo.turn;
+        self.fullmove_number = undo.fullmove_number;
+        self.variant = undo.variant;
+        self.removed.clear();
+        self.clear_selection();
+    }
+
+    //a copy of the current position without the moves of the game and the undo history, on which moves can be tried
+    //even once the game was stopped
+    pub(crate) fn without_history(&self) -> LogicManager {
+        LogicManager {
+            board: self.board.clone(),
+            curr_selected: (-1, -1),
+            possible_moves: vec![],
+            en_passant: self.en_passant,
+            black_king: self.black_king,
+            white_king: self.white_king,
+            hash: self.hash,
+            past_positions: self.past_positions.clone(),
+            halfmove_clock: self.halfmove_clock,
+            claimed_draw: self.claimed_draw,
+            turn: self.turn,
+            fullmove_number: self.fullmove_number,
+            chess960: self.chess960,
+            variant: self.variant.clone(),
+            removed: vec![],
+            start_fen: String::new(),
+            moves: vec![],
+            san_moves: vec![],
+            history: vec![],
+            future: vec![],
+            stop: false,
+            recording: true,
+            taken: vec![],
+        }
+    }
+
+    //must be called right before a move changes the board. Making a new move discards the moves that were taken back
+    pub(crate) fn save_snapshot(&mut self) {
+        let current = self.snapshot();
+        self.history.push(current);
+        self.future.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            en_passant: self.en_passant,
+            black_king: self.black_king,
+            white_king: self.white_king,
+            hash: self.hash,
+            past_positions: self.past_positions.clone(),
+            halfmove_clock: self.halfmove_clock,
+            claimed_draw: self.claimed_draw,
+            turn: self.turn,
+            fullmove_number: self.fullmove_number,
+            variant: self.variant.clone(),
+            moves: self.moves.clone(),
+            san_moves: self.san_moves.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.board = snapshot.board;
+        self.en_passant = snapshot.en_passant;
+        self.black_king = snapshot.black_king;
+        self.white_king = snapshot.white_king;
+        self.hash = snapshot.hash;
+        self.past_positions = snapshot.past_positions;
+        self.halfmove_clock = snapshot.halfmove_clock;
+        self.claimed_draw = snapshot.claimed_draw;
+        self.turn = snapshot.turn;
+        self.fullmove_number = snapshot.fullmove_number;
+        self.variant = snapshot.variant;
+        self.moves = snapshot.moves;
+        self.san_moves = snapshot.san_moves;
+        self.clear_selection();
+        self.stop = false;
+    }
+}
@@ -1,29 +1,30 @@
-use super::{Piece, Board, piece::is_valid_pos, PieceTypes};
+use super::{Piece, Board, piece::{is_valid_pos, Color}, PieceTypes};
+use super::bitboard::{Bitboards, square};
 use colored::*;
 use std::any::Any;
 
 #[derive(Clone)]
 pub struct King {
     pos: (i8, i8),
-    color: bool, //true is white, false is black
+    color: Color,
     first_move: bool,
 }
 
 
 impl Piece for King {
-    fn new(pos: (i8, i8), color: bool) -> Option<Box<dyn Piece>> {
+    fn new(pos: (i8, i8), color: Color) -> Option<Box<dyn Piece>> {
         Some(Box::new(King { pos, color, first_move: true }))
     }
 
     fn print(&self) {
-        if self.color {
+        if self.color == Color::White {
             print!("{}", "k".bright_white());
         } else {
             print!("{}", "k".black());
         }
     }
 
-    fn color(&self) -> bool {
+    fn color(&self) -> Color {
         self.color
     }
 
@@ -32,14 +33,15 @@ impl Piece for King {
         self.first_move = false;
     }
 
-    fn get_possible_moves(&self, board: &Board, en_passant: &Option<(i8, i8)>, _king_pos: (i8, i8), already_called: bool) -> Vec<(i8, i8)> {
+    fn get_possible_moves(&self, board: &Board, _en_passant: &Option<(i8, i8)>, _king_pos: (i8, i8), already_called: bool) -> Vec<(i8, i8)> {
         let mut possible_moves = vec![];
+        let position = King::check_position(board, already_called);
         for dir in [(1, 1), (-1, 1), (1, -1), (-1, -1), (1, 0), (-1, 0), (0, 1), (0, -1)] {
             let to_check = (self.pos.0 + dir.0, self.pos.1 + dir.1);
             if !is_valid_pos(to_check) {
                 continue;
             }
-            if already_called || !self.is_check(&board, self.pos, to_check, en_passant, to_check) {
+            if already_called || !self.is_check(&position, self.pos, to_check, to_check) {
                 if let Some(piece) = board[to_check.0 as usize][to_check.1 as usize].as_ref() {
                     if piece.color() != self.color {
                         possible_moves.push(to_check);
@@ -49,31 +51,12 @@ impl Piece for King {
                 }
             }
         }
-        if self.first_move && !already_called {
-            //Kingside castle
-            if board[self.pos.0 as usize][5].is_none() &&
-                board[self.pos.0 as usize][6].is_none() &&
-                !self.is_check(&board, self.pos, (self.pos.0, 5), en_passant, (self.pos.0, 5)) &&
-                !self.is_check(&board, self.pos, (self.pos.0, 6), en_passant, (self.pos.0, 6)) {
-
-                if let Some(piece) = board[self.pos.0 as usize][7].as_ref() {
-                    if piece.is_first_move() {
-                        possible_moves.push((self.pos.0, 6));
-                    }
-                }
-            }
-
-            //Queenside castle
-            if board[self.pos.0 as usize][3].is_none() &&
-                board[self.pos.0 as usize][2].is_none() &&
-                board[self.pos.0 as usize][1].is_none() &&
-                !self.is_check(&board, self.pos, (self.pos.0, 3), en_passant, (self.pos.0, 3)) &&
-                !self.is_check(&board, self.pos, (self.pos.0, 2), en_passant, (self.pos.0, 2)) &&
-                !self.is_check(&board, self.pos, (self.pos.0, 1), en_passant, (self.pos.0, 1)) {
-
-                if let Some(piece) = board[self.pos.0 as usize][0].as_ref() {
-                    if piece.is_first_move() {
-                        possible_moves.push((self.pos.0, 2));
+        //castling is not allowed out of check. The king castles by moving onto its own rook
+        if self.first_move && !already_called && !self.is_check(&position, self.pos, self.pos, self.pos) {
+            for kingside in [true, false] {
+                if let Some(rook_col) = castling_rook(board, self.pos, self.color, kingside) {
+                    if self.can_castle(board, &position, rook_col, kingside) {
+                        possible_moves.push((self.pos.0, rook_col));
                     }
                 }
             }
@@ -99,34 +82,57 @@ impl Piece for King {
 }
 
 impl King {
-    pub fn is_check(&self, board: &Board, orig_pos: (i8, i8), dest_pos: (i8, i8), en_passant: &Option<(i8, i8)>, king_pos: (i8, i8)) -> bool {
-        let mut board_copy = board.clone();
-        if orig_pos != dest_pos {
-            board_copy[dest_pos.0 as usize][dest_pos.1 as usize] = board_copy[orig_pos.0 as usize][orig_pos.1 as usize].clone();
-            board_copy[orig_pos.0 as usize][orig_pos.1 as usize] = None;
+    //the bitboards of board that is_check tries the moves of a piece on, built once for all of them.
+    //Empty when already_called, since those moves are not checked
+    pub fn check_position(board: &Board, already_called: bool) -> Bitboards {
+        if already_called {
+            Bitboards::default()
+        } else {
+            Bitboards::from_board(board)
         }
-        for row in &board_copy {
-            for sqr in row {
-                if let Some(piece) = sqr {
-                    if piece.color() != self.color && piece.get_possible_moves(&board_copy, en_passant, king_pos, true).contains(&king_pos) {
-                        return true;
-                    }
-                }
-            }
+    }
+
+    //true if the king at king_pos would be attacked after moving the piece at orig_pos to dest_pos in position.
+    //orig_pos == dest_pos tests the current position
+    pub fn is_check(&self, position: &Bitboards, orig_pos: (i8, i8), dest_pos: (i8, i8), king_pos: (i8, i8)) -> bool {
+        let mut after = *position;
+        if orig_pos != dest_pos {
+            after.move_piece(orig_pos, dest_pos);
         }
-        false
+        after.is_attacked(square(king_pos), self.color.opposite())
     }
 
-    pub fn is_checkmate(&self, board: &Board, en_passant: &Option<(i8, i8)>, other_king_pos: (i8, i8)) -> bool {
-        for row in board {
-            for sqr in row {
-                if let Some(piece) = sqr {
-                    if piece.color() == self.color && piece.get_possible_moves(&board, en_passant, other_king_pos, false).len() != 0 {
-                        return false;
-                    }
-                }
-            }
+    //true if the squares between the king, the rook in rook_col and their destinations are empty, the king passes
+    //no attacked square, and it is not in check once both have moved
+    fn can_castle(&self, board: &Board, position: &Bitboards, rook_col: i8, kingside: bool) -> bool {
+        let row = self.pos.0;
+        let (king_to, rook_to) = castling_squares(kingside);
+        let span = |a: i8, b: i8| a.min(b)..=a.max(b);
+        let empty = span(self.pos.1, king_to).chain(span(rook_col, rook_to))
+            .all(|col| col == self.pos.1 || col == rook_col || board[row as usize][col as usize].is_none());
+        let passes_safely = span(self.pos.1, king_to)
+            .filter(|col| *col != self.pos.1 && *col != king_to)
+            .all(|col| !self.is_check(position, self.pos, (row, col), (row, col)));
+        if !empty || !passes_safely {
+            return false;
         }
-        true
+        let mut after = *position;
+        after.castle(self.pos, (row, king_to), (row, rook_col), (row, rook_to));
+        !after.is_attacked(square((row, king_to)), self.color.opposite())
     }
-}
\ No newline at end of file
+}
+
+//the columns the king and the rook end up on when castling to one side, the same in Chess960 as in standard chess
+pub fn castling_squares(kingside: bool) -> (i8, i8) {
+    if kingside { (6, 5) } else { (2, 3) }
+}
+
+//the column of the unmoved rook of color on the king's row that the king at king_pos could castle with on one side.
+//If there are several the outermost is taken. Whether the king itself has moved is up to the caller
+pub fn castling_rook(board: &Board, king_pos: (i8, i8), color: Color, kingside: bool) -> Option<i8> {
+    let cols = if kingside { (king_pos.1 + 1..8).rev().collect::<Vec<_>>() } else { (0..king_pos.1).collect() };
+    cols.into_iter().find(|col| match board[king_pos.0 as usize][*col as usize].as_ref() {
+        Some(piece) => piece.piece_type() == PieceTypes::Rook && piece.color() == color && piece.is_first_move(),
+        None => false,
+    })
+}
@@ -0,0 +1,42 @@
+use super::piece::Color;
+use super::variant::Variant;
+use super::{LogicManager, GameStatus};
+use std::any::Any;
+
+//d5, e5, d4 and e4
+const HILL: [(i8, i8); 4] = [(3, 3), (3, 4), (4, 3), (4, 4)];
+
+//bringing the king to one of the four center squares wins the game, besides checkmate
+#[derive(Clone, Default)]
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn status(&self, lm: &LogicManager) -> Option<GameStatus> {
+        [Color::White, Color::Black].iter().find(|color| HILL.contains(&lm.king_pos(**color))).map(|color| GameStatus::VariantWin(*color))
+    }
+
+    fn win_reason(&self) -> &'static str {
+        "by reaching the hill"
+    }
+
+    //a bare king can still walk to the hill
+    fn material_can_draw(&self, _lm: &LogicManager) -> bool {
+        false
+    }
+
+    fn marked_squares(&self, _lm: &LogicManager) -> Vec<(i8, i8)> {
+        HILL.to_vec()
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
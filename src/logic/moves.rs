@@ -0,0 +1,213 @@
+use super::piece::{PieceTypes, square_name, parse_square};
+use super::san::piece_letter;
+use super::LogicManager;
+use super::king::castling_squares;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+//a square of the board, row 0 is the 8th rank and col 0 is the a file, like the indices of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Square {
+    pub row: i8,
+    pub col: i8,
+}
+
+impl Square {
+    pub fn new(row: i8, col: i8) -> Square {
+        Square { row, col }
+    }
+
+    //parses an algebraic square name, "e1" -> row 7, col 4. Returns None if the name is invalid
+    pub fn from_name(name: &str) -> Option<Square> {
+        parse_square(name).map(Square::from)
+    }
+
+    //the algebraic name of the square, e.g. "e4"
+    pub fn name(&self) -> String {
+        square_name(self.pos())
+    }
+
+    //the square as the (row, col) position used to index the board
+    pub fn pos(&self) -> (i8, i8) {
+        (self.row, self.col)
+    }
+}
+
+impl From<(i8, i8)> for Square {
+    fn from(pos: (i8, i8)) -> Square {
+        Square::new(pos.0, pos.1)
+    }
+}
+
+impl From<Square> for (i8, i8) {
+    fn from(sqr: Square) -> (i8, i8) {
+        sqr.pos()
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+//what a move does besides moving a piece from one square to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MoveKind {
+    Normal,
+    Capture,
+    EnPassant,
+    Castle,
+    Promotion, //with or without a capture
+    Drop(PieceTypes), //a piece from the hand of the player put on the empty square to, from is the same square
+}
+
+//a move of the piece at from to to. promotion is the piece a pawn reaching the last rank turns into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceTypes>,
+    pub kind: MoveKind,
+}
+
+impl Move {
+    pub fn new(from: Square, to: Square, promotion: Option<PieceTypes>, kind: MoveKind) -> Move {
+        Move { from, to, promotion, kind }
+    }
+
+    //the move of dropping a piece of piece_type from the hand of the player onto to
+    pub fn piece_drop(piece_type: PieceTypes, to: Square) -> Move {
+        Move::new(to, to, None, MoveKind::Drop(piece_type))
+    }
+
+    //true if the move takes a piece of the opponent, including en passant and capturing promotions.
+    //lm must be the position before the move
+    pub fn is_capture(&self, lm: &LogicManager) -> bool {
+        match self.kind {
+            MoveKind::EnPassant => true,
+            MoveKind::Castle => false, //in Chess960 to is the square of the king's own rook
+            MoveKind::Drop(_) => false,
+            _ => lm.get_piece_color(self.to.pos()).is_some(),
+        }
+    }
+
+    //the square the moving piece ends up on. That is to, except for castling in Chess960 where to is the rook's square
+    pub fn destination(&self) -> Square {
+        match self.kind {
+            MoveKind::Castle => Square::new(self.from.row, castling_squares(self.to.col > self.from.col).0),
+            _ => self.to,
+        }
+    }
+
+    //returns the move in the long algebraic notation used by UCI, e.g. "e7e8q", or "N@f3" for drops
+    pub fn to_uci(&self) -> String {
+        if let MoveKind::Drop(piece_type) = self.kind {
+            return format!("{}@{}", piece_letter(piece_type), self.to);
+        }
+        let mut res = self.from.name() + &self.to.name();
+        if let Some(promotion) = self.promotion {
+            res.push(piece_letter(promotion).to_ascii_lowercase());
+        }
+        res
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_uci())
+    }
+}
+
+//every change a move made to the board, so front-ends can update their pieces without comparing boards
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveOutcome {
+    pub mv: Move,
+    pub moved: PieceTypes, //the type of the piece that moved, a pawn for promotions
+    pub captured: Option<(Square, PieceTypes)>, //the square is not mv.to when capturing en passant
+    pub rook_move: Option<(Square, Square)>, //the rook's move when castling
+    //the pieces the variant took off the board after the move, like the explosion of a capture in atomic chess.
+    //The piece that moved is among them, on its destination, if it was taken off too
+    pub removed: Vec<(Square, PieceTypes)>,
+    pub san: String, //the move in Standard Algebraic Notation, with the check suffix
+    pub check: bool, //true if the move put the opponent in check
+}
+
+//the error of trying to make a move that is not legal in the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IllegalMove(pub Move);
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a legal move", self.0)
+    }
+}
+
+impl std::error::Error for IllegalMove {}
+
+impl LogicManager {
+    //returns every legal move of the side to move, with one move per promotion choice, and the drops of variants that have them
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        for (i, row) in self.board.iter().enumerate() {
+            for (j, sqr) in row.iter().enumerate() {
+                let piece = match sqr {
+                    Some(piece) if piece.color() == self.turn => piece,
+                    _ => continue,
+                };
+                let from = (i as i8, j as i8);
+                for to in self.piece_moves(from) {
+                    if piece.piece_type() == PieceTypes::Pawn && (to.0 == 0 || to.0 == 7) {
+                        moves.extend(self.variant.promotions().into_iter().map(|promotion| self.create_move(from, to, Some(promotion))));
+                    } else {
+                        moves.push(self.create_move(from, to, None));
+                    }
+                }
+            }
+        }
+        moves.extend(self.drop_moves());
+        moves
+    }
+
+    //checks that mv is one of the legal moves of the side to move and makes it. Unlike move_piece it does not
+    //need a piece to be selected first, and any selection is cleared. An illegal move leaves the position unchanged
+    pub fn make_move(&mut self, mv: Move) -> Result<MoveOutcome, IllegalMove> {
+        if !self.legal_moves().contains(&mv) {
+            return Err(IllegalMove(mv));
+        }
+        self.apply_move(mv).ok_or(IllegalMove(mv))
+    }
+
+    //builds the move of the piece at from to to, working out its kind from the board. Must be called before the move is made
+    pub(crate) fn create_move(&self, from: (i8, i8), to: (i8, i8), promotion: Option<PieceTypes>) -> Move {
+        let piece_type = self.get_piece(from).piece_type();
+        let kind = if promotion.is_some() {
+            MoveKind::Promotion
+        } else if piece_type == PieceTypes::King && self.castling_rook_of(from, to).is_some() {
+            MoveKind::Castle
+        } else if self.get_piece_color(to).is_some() {
+            MoveKind::Capture
+        } else if piece_type == PieceTypes::Pawn && from.1 != to.1 {
+            MoveKind::EnPassant
+        } else {
+            MoveKind::Normal
+        };
+        Move::new(from.into(), to.into(), promotion, kind)
+    }
+
+    //the outcome of the move just made, read from the recorded SAN. The moves of a search are not written down
+    pub(crate) fn outcome(&self, mv: Move, moved: PieceTypes, captured: Option<(Square, PieceTypes)>, rook_move: Option<(Square, Square)>) -> MoveOutcome {
+        if !self.recording {
+            let check = self.is_check(self.turn);
+            return MoveOutcome { mv, moved, captured, rook_move, removed: self.removed.clone(), san: String::new(), check };
+        }
+        let san = self.san_moves.last().cloned().unwrap_or_default();
+        let check = san.ends_with('+') || san.ends_with('#');
+        MoveOutcome { mv, moved, captured, rook_move, removed: self.removed.clone(), san, check }
+    }
+}
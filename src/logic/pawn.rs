@@ -1,4 +1,4 @@
-use super::piece::{Piece, Board, is_valid_pos, PieceTypes};
+use super::piece::{Piece, Board, is_valid_pos, PieceTypes, Color};
 use super::King;
 use colored::*;
 use std::any::Any;
@@ -6,25 +6,25 @@ use std::any::Any;
 #[derive(Clone)]
 pub struct Pawn {
     pos: (i8, i8),
-    color: bool, //true is white, false is black
+    color: Color,
     first_move: bool,
     en_passant: Option<(i8, i8)>,
 }
 
 impl Piece for Pawn {
-    fn new(pos: (i8, i8), color: bool) -> Option<Box<dyn Piece>> {
+    fn new(pos: (i8, i8), color: Color) -> Option<Box<dyn Piece>> {
         Some(Box::new(Pawn { pos, color, first_move: true, en_passant: None}))
     }
 
     fn print(&self) {
-        if self.color {
+        if self.color == Color::White {
             print!("{}", "p".bright_white());
         } else {
             print!("{}", "p".black());
         }
     }
 
-    fn color(&self) -> bool {
+    fn color(&self) -> Color {
         self.color
     }
 
@@ -40,25 +40,31 @@ impl Piece for Pawn {
 
     fn get_possible_moves(&self, board: &Board, en_passant: &Option<(i8, i8)>, king_pos: (i8, i8), already_called: bool) -> Vec<(i8, i8)> {
         let dir = match self.color {
-            true => -1,
-            false => 1,
+            Color::White => -1,
+            Color::Black => 1,
         };
         let king = board[king_pos.0 as usize][king_pos.1 as usize].as_ref().unwrap().as_any().downcast_ref::<King>().unwrap();
+        let position = King::check_position(board, already_called);
         let mut possible_moves = vec![];
-        if is_valid_pos((self.pos.0 + dir, self.pos.1)) && board[(self.pos.0 + dir) as usize][self.pos.1 as usize].is_none() && (already_called || !king.is_check(&board, self.pos, (self.pos.0 + dir, self.pos.1), en_passant, king_pos)) {
-            possible_moves.push((self.pos.0 + dir, self.pos.1));
-            if is_valid_pos((self.pos.0 + 2 * dir, self.pos.1)) && self.first_move && board[(self.pos.0 + 2 * dir) as usize][self.pos.1 as usize].is_none() && (already_called || !king.is_check(&board, self.pos, (self.pos.0 + 2 * dir, self.pos.1), en_passant, king_pos)) {
-                possible_moves.push((self.pos.0 + 2 * dir, self.pos.1));
+        let one_step = (self.pos.0 + dir, self.pos.1);
+        if is_valid_pos(one_step) && board[one_step.0 as usize][one_step.1 as usize].is_none() {
+            if already_called || !king.is_check(&position, self.pos, one_step, king_pos) {
+                possible_moves.push(one_step);
+            }
+            //the double step only needs the square in between to be empty, the single step may still leave the king in check
+            let two_steps = (self.pos.0 + 2 * dir, self.pos.1);
+            if is_valid_pos(two_steps) && self.first_move && board[two_steps.0 as usize][two_steps.1 as usize].is_none() && (already_called || !king.is_check(&position, self.pos, two_steps, king_pos)) {
+                possible_moves.push(two_steps);
             }
         }
         for i in [-1, 1] {
             if is_valid_pos((self.pos.0 + dir, self.pos.1 + i)) {
                 if let Some(piece) = &board[(self.pos.0 + dir) as usize][(self.pos.1 + i) as usize] {
-                    if piece.color() != self.color && (already_called || !king.is_check(&board, self.pos, (self.pos.0 + dir, self.pos.1 + i), en_passant, king_pos)) {
+                    if piece.color() != self.color && (already_called || !king.is_check(&position, self.pos, (self.pos.0 + dir, self.pos.1 + i), king_pos)) {
                         possible_moves.push((self.pos.0 + dir, self.pos.1 + i));
                     }
                 }
-                if en_passant.eq(&Some((self.pos.0 + dir, self.pos.1 + i))) && (already_called || !king.is_check(&board, self.pos, (self.pos.0 + dir, self.pos.1 + i), en_passant, king_pos)) {
+                if en_passant.eq(&Some((self.pos.0 + dir, self.pos.1 + i))) && (already_called || !king.is_check(&position, self.pos, (self.pos.0 + dir, self.pos.1 + i), king_pos)) {
                     possible_moves.push((self.pos.0 + dir, self.pos.1 + i));
                 }
             }
@@ -71,7 +77,7 @@ impl Piece for Pawn {
     }
 
     fn possible_en_passant(&self) -> Option<(i8, i8)> {
-        self.en_passant.clone()
+        self.en_passant
     }
 
     fn took_using_en_passant(&self, new_pos: (i8, i8), board: &Board) -> bool {
@@ -0,0 +1,35 @@
+use super::{LogicManager, Move};
+
+impl LogicManager {
+    //counts the leaf nodes of the legal move tree of the given depth, used to verify move generation
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for mv in moves {
+            if let Some(undo) = self.make_search_move(mv) {
+                nodes += self.perft(depth - 1);
+                self.unmake_search_move(undo);
+            }
+        }
+        nodes
+    }
+
+    //returns the perft node count of depth - 1 after each legal move, which helps locating move generation bugs
+    pub fn divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+        let mut res = vec![];
+        for mv in self.legal_moves() {
+            if let Some(undo) = self.make_search_move(mv) {
+                res.push((mv, self.perft(depth.saturating_sub(1))));
+                self.unmake_search_move(undo);
+            }
+        }
+        res
+    }
+
+}
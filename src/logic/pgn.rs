@@ -0,0 +1,478 @@
+use super::{LogicManager, GameStatus, Color, FenError, STARTING_FEN};
+use super::variant::{Variant, Standard, variant_from_name};
+use std::fmt;
+use std::fs;
+use std::io;
+
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+const LINE_WIDTH: usize = 80;
+
+//a game in Portable Game Notation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pgn {
+    pub tags: Vec<(String, String)>,
+    pub comments: Vec<String>, //comments before the first move
+    pub moves: Vec<PgnMove>,
+    pub result: String,
+}
+
+//a move of the movetext together with its annotations
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PgnMove {
+    pub san: String,
+    pub nags: Vec<u8>,
+    pub leading_comments: Vec<String>, //comments before the move, as at the start of a variation
+    pub comments: Vec<String>,
+    pub variations: Vec<Vec<PgnMove>>, //alternatives to this move
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    Syntax(String),
+    Fen(FenError),
+    IllegalMove { move_number: u16, san: String },
+    UnknownVariant(String),
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PgnError::Syntax(msg) => write!(f, "invalid PGN: {}", msg),
+            PgnError::Fen(e) => write!(f, "invalid FEN tag: {}", e),
+            PgnError::IllegalMove { move_number, san } => write!(f, "illegal move {} at move {}", san, move_number),
+            PgnError::UnknownVariant(name) => write!(f, "unknown variant '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+//describes why a game saved in a PGN file could not be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Pgn(PgnError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Pgn(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl Pgn {
+    //creates a game record with the seven tag roster filled with unknown values
+    pub fn new() -> Pgn {
+        let mut pgn = Pgn {
+            result: "*".to_string(),
+            ..Default::default()
+        };
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Date" => "????.??.??",
+                "Result" => "*",
+                _ => "?",
+            };
+            pgn.set_tag(name, value);
+        }
+        pgn
+    }
+
+    //records the moves played in lm, along with its starting position and result
+    pub fn from_game(lm: &LogicManager) -> Pgn {
+        let mut pgn = Pgn::new();
+        let start_fen = lm.get_start_fen();
+        if lm.variant().name() != Standard.name() {
+            pgn.set_tag("Variant", lm.variant().name());
+        } else if lm.is_chess960() {
+            pgn.set_tag("Variant", "Chess960");
+        }
+        if start_fen != lm.variant().starting_fen() || lm.is_chess960() {
+            pgn.set_tag("SetUp", "1");
+            pgn.set_tag("FEN", start_fen);
+        }
+        pgn.moves = lm.get_san_moves().iter().map(|san| PgnMove {
+            san: san.clone(),
+            ..Default::default()
+        }).collect();
+        pgn.set_result(result_string(lm.status()));
+        pgn
+    }
+
+    //parses the first game in text
+    pub fn parse(text: &str) -> Result<Pgn, PgnError> {
+        Parser::new(text).parse_game()
+    }
+
+    //parses every game in text, like the games of a PGN database
+    pub fn parse_all(text: &str) -> Result<Vec<Pgn>, PgnError> {
+        let mut parser = Parser::new(text);
+        let mut games = vec![];
+        parser.skip_whitespace();
+        while parser.peek().is_some() {
+            games.push(parser.parse_game()?);
+            parser.skip_whitespace();
+        }
+        Ok(games)
+    }
+
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(n, _v)| n == name).map(|(_n, v)| v.as_str())
+    }
+
+    //sets the value of a tag, adding it after the existing tags if it is new
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _v)| n == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn set_result(&mut self, result: &str) {
+        self.result = result.to_string();
+        self.set_tag("Result", result);
+    }
+
+    //plays the main line of the game from its starting position, by the rules of the variant in the Variant tag.
+    //Variations are not played. A game recorded as drawn while a draw could be claimed had the draw claimed. Results the
+    //rules do not give, like agreed draws or resignations, are only kept in the result of the PGN and the game is left going on
+    pub fn replay(&self) -> Result<LogicManager, PgnError> {
+        let name = self.get_tag("Variant").unwrap_or("Standard");
+        let chess960 = ["chess960", "fischerandom"].contains(&name.to_ascii_lowercase().as_str());
+        let variant: Box<dyn Variant> = if chess960 {
+            Box::new(Standard)
+        } else {
+            variant_from_name(name).ok_or_else(|| PgnError::UnknownVariant(name.to_string()))?
+        };
+        let fen = self.get_tag("FEN").map(str::to_string).unwrap_or_else(|| variant.starting_fen());
+        let mut lm = if chess960 {
+            LogicManager::from_fen_chess960(&fen)
+        } else {
+            LogicManager::from_fen_with_variant(&fen, variant)
+        }.map_err(PgnError::Fen)?;
+        for pgn_move in &self.moves {
+            let applied = lm.parse_san(&pgn_move.san).ok().and_then(|mv| lm.apply_move(mv));
+            if applied.is_none() {
+                return Err(PgnError::IllegalMove { move_number: lm.fullmove_number, san: pgn_move.san.clone() });
+            }
+        }
+        if self.result == "1/2-1/2" && lm.claimable_draw().is_some() {
+            lm.claim_draw();
+        }
+        Ok(lm)
+    }
+
+    //writes the game to the file at path. The starting position and every move are kept, so moves can still be taken back
+    //after loading
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    //the fullmove number and side to move of the first move
+    fn first_move(&self) -> (u16, Color) {
+        let fields = self.get_tag("FEN").unwrap_or(STARTING_FEN).split_whitespace().collect::<Vec<_>>();
+        let turn = if fields.get(1) == Some(&"b") { Color::Black } else { Color::White };
+        let number = fields.get(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+        (number, turn)
+    }
+}
+
+impl LogicManager {
+    //replays the game saved in the PGN file at path
+    pub fn load_pgn(path: &str) -> Result<LogicManager, LoadError> {
+        let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+        Pgn::parse(&text).and_then(|pgn| pgn.replay()).map_err(LoadError::Pgn)
+    }
+}
+
+impl fmt::Display for Pgn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        writeln!(f)?;
+
+        let mut tokens = self.comments.iter().map(|c| format!("{{{}}}", c)).collect::<Vec<_>>();
+        let (number, turn) = self.first_move();
+        write_moves(&mut tokens, &self.moves, number, turn);
+        tokens.push(self.result.clone());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + token.len() + 1 > LINE_WIDTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        writeln!(f, "{}", line)
+    }
+}
+
+//returns the PGN result token for a game status
+pub fn result_string(status: GameStatus) -> &'static str {
+    match status {
+        GameStatus::Ongoing => "*",
+        _ => match status.winner() {
+            Some(Color::White) => "1-0",
+            Some(Color::Black) => "0-1",
+            None => "1/2-1/2",
+        },
+    }
+}
+
+//appends the movetext of moves, starting at the given move number and side to move, to tokens
+fn write_moves(tokens: &mut Vec<String>, moves: &[PgnMove], mut number: u16, mut turn: Color) {
+    let mut need_number = true;
+    for pgn_move in moves {
+        for comment in &pgn_move.leading_comments {
+            tokens.push(format!("{{{}}}", comment));
+            need_number = true;
+        }
+        //the move number is kept in the same token as the move so that lines are never broken between them
+        if turn == Color::White {
+            tokens.push(format!("{}. {}", number, pgn_move.san));
+        } else if need_number {
+            tokens.push(format!("{}... {}", number, pgn_move.san));
+        } else {
+            tokens.push(pgn_move.san.clone());
+        }
+        need_number = false;
+        for nag in &pgn_move.nags {
+            tokens.push(format!("${}", nag));
+        }
+        for comment in &pgn_move.comments {
+            tokens.push(format!("{{{}}}", comment));
+            need_number = true;
+        }
+        for variation in &pgn_move.variations {
+            let mut variation_tokens = vec![];
+            write_moves(&mut variation_tokens, variation, number, turn);
+            tokens.push(format!("({}", variation_tokens.first().cloned().unwrap_or_default()));
+            tokens.extend(variation_tokens.into_iter().skip(1));
+            let last = tokens.pop().unwrap();
+            tokens.push(last + ")");
+            need_number = true;
+        }
+        if turn == Color::Black {
+            number += 1;
+        }
+        turn = turn.opposite();
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Parser<'a> {
+        Parser { text, pos: 0 }
+    }
+
+    fn parse_game(&mut self) -> Result<Pgn, PgnError> {
+        let mut pgn = Pgn::default();
+        self.skip_whitespace();
+        while self.peek() == Some('[') {
+            let (name, value) = self.parse_tag()?;
+            pgn.set_tag(&name, &value);
+            self.skip_whitespace();
+        }
+        while self.peek() == Some('{') || self.peek() == Some(';') {
+            pgn.comments.push(self.parse_comment()?);
+            self.skip_whitespace();
+        }
+        let (moves, result) = self.parse_moves(0)?;
+        pgn.moves = moves;
+        pgn.result = result.unwrap_or_else(|| pgn.get_tag("Result").unwrap_or("*").to_string());
+        Ok(pgn)
+    }
+
+    fn parse_tag(&mut self) -> Result<(String, String), PgnError> {
+        self.next();
+        self.skip_whitespace();
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if name.is_empty() {
+            return Err(PgnError::Syntax("tag without a name".to_string()));
+        }
+        self.skip_whitespace();
+        if self.next() != Some('"') {
+            return Err(PgnError::Syntax(format!("tag {} has no quoted value", name)));
+        }
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('\\') => match self.next() {
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some('"') => break,
+                Some(c) => value.push(c),
+                None => return Err(PgnError::Syntax(format!("unterminated value of tag {}", name))),
+            }
+        }
+        self.skip_whitespace();
+        if self.next() != Some(']') {
+            return Err(PgnError::Syntax(format!("tag {} is not closed", name)));
+        }
+        Ok((name, value))
+    }
+
+    fn parse_comment(&mut self) -> Result<String, PgnError> {
+        if self.next() == Some(';') {
+            return Ok(self.take_while(|c| c != '\n').trim().to_string());
+        }
+        let comment = self.take_while(|c| c != '}');
+        if self.next() != Some('}') {
+            return Err(PgnError::Syntax("unterminated comment".to_string()));
+        }
+        Ok(comment.trim().to_string())
+    }
+
+    //parses moves until the end of the game or of the current variation, returns them and the game result if found
+    fn parse_moves(&mut self, depth: usize) -> Result<(Vec<PgnMove>, Option<String>), PgnError> {
+        let mut moves: Vec<PgnMove> = vec![];
+        //comments that come before the first move, kept for it
+        let mut leading_comments = vec![];
+        loop {
+            self.skip_whitespace();
+            let c = match self.peek() {
+                Some(c) => c,
+                None if depth == 0 && leading_comments.is_empty() => return Ok((moves, None)),
+                None if depth == 0 => return Err(PgnError::Syntax("comment without a move".to_string())),
+                None => return Err(PgnError::Syntax("unterminated variation".to_string())),
+            };
+            match c {
+                '{' | ';' => {
+                    let comment = self.parse_comment()?;
+                    match moves.last_mut() {
+                        Some(last) => last.comments.push(comment),
+                        None => leading_comments.push(comment),
+                    }
+                }
+                '(' => {
+                    self.next();
+                    let (variation, _result) = self.parse_moves(depth + 1)?;
+                    match moves.last_mut() {
+                        Some(last) => last.variations.push(variation),
+                        None => return Err(PgnError::Syntax("variation before the first move".to_string())),
+                    }
+                }
+                ')' if depth > 0 => {
+                    if !leading_comments.is_empty() {
+                        return Err(PgnError::Syntax("comment without a move".to_string()));
+                    }
+                    self.next();
+                    return Ok((moves, None));
+                }
+                '$' => {
+                    self.next();
+                    let nag = self.take_while(|c| c.is_ascii_digit());
+                    let nag = nag.parse::<u8>().map_err(|_| PgnError::Syntax(format!("invalid NAG ${}", nag)))?;
+                    self.add_nag(&mut moves, nag)?;
+                }
+                '[' if depth == 0 => return Ok((moves, None)), //start of the next game
+                _ => {
+                    let token = self.take_while(|c| !c.is_whitespace() && !"{;()$".contains(c));
+                    if token.is_empty() {
+                        return Err(PgnError::Syntax(format!("unexpected character '{}'", c)));
+                    }
+                    if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token.as_str()) {
+                        if depth == 0 {
+                            return Ok((moves, Some(token)));
+                        }
+                        continue;
+                    }
+                    //move numbers may be written together with the move, as in "1.e4". Only digits followed by a dot are
+                    //a move number, castling may be written with zeros ("0-0")
+                    let digits = token.len() - token.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    let san = if digits == token.len() || (digits > 0 && token[digits..].starts_with('.')) {
+                        token[digits..].trim_start_matches('.')
+                    } else {
+                        token.as_str()
+                    };
+                    if san.is_empty() {
+                        continue;
+                    }
+                    let annotation_start = san.find(['!', '?']).unwrap_or(san.len());
+                    moves.push(PgnMove {
+                        san: san[..annotation_start].to_string(),
+                        leading_comments: std::mem::take(&mut leading_comments),
+                        ..Default::default()
+                    });
+                    if annotation_start != san.len() {
+                        let nag = suffix_nag(&san[annotation_start..])
+                            .ok_or_else(|| PgnError::Syntax(format!("invalid annotation {}", &san[annotation_start..])))?;
+                        self.add_nag(&mut moves, nag)?;
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_nag(&self, moves: &mut [PgnMove], nag: u8) -> Result<(), PgnError> {
+        match moves.last_mut() {
+            Some(last) => {
+                last.nags.push(nag);
+                Ok(())
+            }
+            None => Err(PgnError::Syntax("NAG before the first move".to_string())),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            self.take_while(char::is_whitespace);
+            //lines starting with % are escaped and ignored
+            let line_start = self.pos == 0 || self.text[..self.pos].ends_with('\n');
+            if line_start && self.peek() == Some('%') {
+                self.take_while(|c| c != '\n');
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        self.text[start..self.pos].to_string()
+    }
+}
+
+//converts traditional move suffix annotations to their NAG
+fn suffix_nag(suffix: &str) -> Option<u8> {
+    match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
@@ -1,7 +1,10 @@
 pub type Board = Vec<Vec<Option<Box<dyn Piece>>>>;
 use std::any::Any;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PieceTypes {
     King,
     Queen,
@@ -11,19 +14,63 @@ pub enum PieceTypes {
     Pawn,
 }
 
+//the color of a piece or a player. The values index the tables that are kept per color, black is 0 and white is 1
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Color {
+    White = 1,
+    Black = 0,
+}
+
+impl Color {
+    pub fn opposite(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    pub fn is_white(self) -> bool {
+        self == Color::White
+    }
+}
+
+//converts the booleans that used to stand for colors, true is white
+impl From<bool> for Color {
+    fn from(white: bool) -> Color {
+        if white { Color::White } else { Color::Black }
+    }
+}
+
 pub fn is_valid_pos(pos: (i8, i8)) -> bool {
     pos.0 < 8 && pos.0 >= 0 && pos.1 < 8 && pos.1 >= 0
 }
 
+//converts a board position into its algebraic name, (7, 4) -> "e1"
+pub fn square_name(pos: (i8, i8)) -> String {
+    format!("{}{}", (b'a' + pos.1 as u8) as char, 8 - pos.0)
+}
+
+//converts an algebraic square name into a board position, "e1" -> (7, 4). Returns None if the name is invalid
+pub fn parse_square(name: &str) -> Option<(i8, i8)> {
+    let mut chars = name.chars();
+    let file = chars.next()?;
+    let rank = chars.next()?;
+    if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+    Some(('8' as i8 - rank as i8, file as i8 - 'a' as i8))
+}
+
 pub trait Piece {
     //Gets position and color of piece, returns piece object
-    fn new(pos: (i8, i8), color: bool) -> Option<Box<dyn Piece>> where Self: Sized;
+    #[allow(clippy::new_ret_no_self)]
+    fn new(pos: (i8, i8), color: Color) -> Option<Box<dyn Piece>> where Self: Sized;
     //checks if the piece is a king
     fn piece_type(&self) -> PieceTypes;
     //prints the piece in ascii
     fn print(&self);
-    //returns the color of the piece, true is white, false is black
-    fn color(&self) -> bool;
+    fn color(&self) -> Color;
     //tells the piece to change its position data to pos. Must be called after get_possible_moves
     fn move_piece(&mut self, pos: (i8, i8));
     //gets all possible current moves and returns them. Must be called before move_piece
@@ -0,0 +1,88 @@
+use super::{LogicManager, GameStatus, Move, FenError, IllegalMove};
+use super::variant::variant_from_name;
+use std::convert::TryFrom;
+use std::fmt;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+//a game as the position it started from and the moves played since. Loading it replays the moves,
+//so the undo history, repetitions and clocks are rebuilt exactly as they were
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRecord {
+    pub start_fen: String,
+    pub moves: Vec<Move>,
+    pub undone: Vec<Move>, //moves taken back that can still be redone, the next one to redo first
+    pub claimed_draw: Option<GameStatus>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub chess960: bool, //records written before Chess960 support have no such field
+    #[cfg_attr(feature = "serde", serde(default = "standard_name"))]
+    pub variant: String, //the name of the variant, standard chess for records written before variants
+}
+
+#[cfg(feature = "serde")]
+fn standard_name() -> String {
+    "Standard".to_string()
+}
+
+//describes why a game record could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameRecordError {
+    Fen(FenError),
+    IllegalMove(IllegalMove),
+    NoDrawToClaim(GameStatus),
+    UnknownVariant(String),
+}
+
+impl fmt::Display for GameRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameRecordError::Fen(e) => write!(f, "invalid starting position: {}", e),
+            GameRecordError::IllegalMove(e) => write!(f, "{}", e),
+            GameRecordError::NoDrawToClaim(status) => write!(f, "{:?} can not be claimed in the final position", status),
+            GameRecordError::UnknownVariant(name) => write!(f, "unknown variant '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for GameRecordError {}
+
+impl LogicManager {
+    pub fn to_record(&self) -> GameRecord {
+        GameRecord {
+            start_fen: self.get_start_fen().to_string(),
+            moves: self.get_moves().clone(),
+            undone: self.get_undone_moves().to_vec(),
+            claimed_draw: self.claimed_draw,
+            chess960: self.chess960,
+            variant: self.variant.name().to_string(),
+        }
+    }
+}
+
+impl From<LogicManager> for GameRecord {
+    fn from(lm: LogicManager) -> GameRecord {
+        lm.to_record()
+    }
+}
+
+impl TryFrom<GameRecord> for LogicManager {
+    type Error = GameRecordError;
+
+    fn try_from(record: GameRecord) -> Result<LogicManager, GameRecordError> {
+        let variant = variant_from_name(&record.variant).ok_or(GameRecordError::UnknownVariant(record.variant))?;
+        let mut lm = LogicManager::parse_fen(&record.start_fen, record.chess960, variant).map_err(GameRecordError::Fen)?;
+        for mv in record.moves.iter().chain(record.undone.iter()) {
+            lm.make_move(*mv).map_err(GameRecordError::IllegalMove)?;
+        }
+        for _ in &record.undone {
+            lm.undo();
+        }
+        if let Some(status) = record.claimed_draw {
+            if lm.claim_draw() != Some(status) {
+                return Err(GameRecordError::NoDrawToClaim(status));
+            }
+        }
+        Ok(lm)
+    }
+}
@@ -0,0 +1,252 @@
+use super::piece::{PieceTypes, Color, square_name, parse_square};
+use super::{LogicManager, Move, MoveKind, MoveOutcome};
+use super::drops::drop_san;
+use std::fmt;
+
+//describes why a move in algebraic notation could not be understood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanError {
+    Invalid(String),
+    Illegal(String),
+    Ambiguous(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SanError::Invalid(san) => write!(f, "'{}' is not a move in algebraic notation", san),
+            SanError::Illegal(san) => write!(f, "'{}' is not a legal move", san),
+            SanError::Ambiguous(san) => write!(f, "'{}' could describe more than one move", san),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+impl Move {
+    //returns the move in Standard Algebraic Notation, including the check or checkmate suffix. lm must be the position before the move
+    pub fn to_san(&self, lm: &LogicManager) -> String {
+        let san = match self.kind {
+            MoveKind::Drop(piece_type) => drop_san(piece_type, self.to.pos()),
+            _ => lm.san_without_suffix(self.from.pos(), self.to.pos(), self.promotion),
+        };
+        let mut after = lm.without_history();
+        after.make_search_move(*self);
+        san + after.check_suffix()
+    }
+}
+
+impl LogicManager {
+    //parses a move of the side to move written in Standard Algebraic Notation ("Nxe5+", "O-O", "e8=Q", "N@f3"),
+    //long algebraic notation ("Ng1-f3", "e7xd8=Q") or UCI notation ("e7e8q"). Check and annotation suffixes are ignored
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let trimmed = san.trim().trim_end_matches(|c| "+#!?".contains(c));
+        //drops name the piece and the square, a pawn drop may leave out the P
+        if let Some((piece, sqr)) = trimmed.split_once('@') {
+            let piece_type = match piece {
+                "" | "P" => PieceTypes::Pawn,
+                _ => piece.chars().next().filter(|_| piece.len() == 1).and_then(letter_piece)
+                    .ok_or_else(|| SanError::Invalid(san.to_string()))?,
+            };
+            let to = parse_square(sqr).ok_or_else(|| SanError::Invalid(san.to_string()))?;
+            if !self.drop_squares(piece_type).contains(&to) {
+                return Err(SanError::Illegal(san.to_string()));
+            }
+            return Ok(Move::piece_drop(piece_type, to.into()));
+        }
+        let king_pos = self.king_pos(self.turn);
+        let castle_side = match trimmed {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(kingside) = castle_side {
+            let castle = self.piece_moves(king_pos).into_iter()
+                .find(|to| self.castling_rook_of(king_pos, *to).is_some_and(|rook| (rook.1 > king_pos.1) == kingside));
+            return match castle {
+                Some(to) => Ok(self.create_move(king_pos, to, None)),
+                None => Err(SanError::Illegal(san.to_string())),
+            };
+        }
+
+        let mut chars = trimmed.chars().filter(|c| *c != 'x' && *c != '-' && *c != '=').collect::<Vec<_>>();
+        let piece_type = match chars.first().copied().and_then(letter_piece) {
+            Some(piece_type) => {
+                chars.remove(0);
+                Some(piece_type)
+            }
+            None => None,
+        };
+
+        let mut promotion = None;
+        if piece_type.is_none() {
+            if let Some(last) = chars.last().map(|c| c.to_ascii_uppercase()).and_then(letter_piece) {
+                promotion = Some(last);
+                chars.pop();
+            }
+        }
+        if chars.len() < 2 || chars.len() > 4 {
+            return Err(SanError::Invalid(san.to_string()));
+        }
+        let to = parse_square(&chars[chars.len() - 2..].iter().collect::<String>())
+            .ok_or_else(|| SanError::Invalid(san.to_string()))?;
+        let disambiguation = &chars[..chars.len() - 2];
+
+        let piece_type = piece_type.unwrap_or(PieceTypes::Pawn);
+        let mut candidates = self.pieces_of(piece_type, self.turn).into_iter().filter(|pos| {
+            let name = square_name(*pos).chars().collect::<Vec<_>>();
+            disambiguation.iter().all(|c| name.contains(c)) && self.piece_moves(*pos).contains(&to)
+        });
+        //a long algebraic move names its origin square, and may leave out the piece letter
+        let from = if disambiguation.len() == 2 && piece_type == PieceTypes::Pawn {
+            let from = parse_square(&disambiguation.iter().collect::<String>())
+                .ok_or_else(|| SanError::Invalid(san.to_string()))?;
+            if self.get_piece_color(from) != Some(self.turn) || !self.piece_moves(from).contains(&to) {
+                return Err(SanError::Illegal(san.to_string()));
+            }
+            from
+        } else {
+            let from = candidates.next().ok_or_else(|| SanError::Illegal(san.to_string()))?;
+            if candidates.next().is_some() {
+                return Err(SanError::Ambiguous(san.to_string()));
+            }
+            from
+        };
+
+        let moved_type = self.get_piece(from).piece_type();
+        let promotes = moved_type == PieceTypes::Pawn && (to.0 == 0 || to.0 == 7);
+        if promotes != promotion.is_some() || promotion.is_some_and(|piece_type| !self.variant.promotions().contains(&piece_type)) {
+            return Err(SanError::Illegal(san.to_string()));
+        }
+        Ok(self.create_move(from, to, promotion))
+    }
+
+    //returns the Standard Algebraic Notation of moving the piece at from to to, without the check suffix.
+    //Must be called before the move is made
+    pub(crate) fn san_without_suffix(&self, from: (i8, i8), to: (i8, i8), promotion: Option<PieceTypes>) -> String {
+        let piece = self.get_piece(from);
+        let piece_type = piece.piece_type();
+        if let Some(rook) = self.castling_rook_of(from, to) {
+            return if rook.1 > from.1 { "O-O" } else { "O-O-O" }.to_string();
+        }
+
+        let mut san = String::new();
+        let is_capture = self.board[to.0 as usize][to.1 as usize].is_some();
+        if piece_type == PieceTypes::Pawn {
+            if from.1 != to.1 {
+                san.push((b'a' + from.1 as u8) as char);
+                san.push('x');
+            }
+            san.push_str(&square_name(to));
+            if let Some(promotion) = promotion {
+                san.push('=');
+                san.push(piece_letter(promotion));
+            }
+            return san;
+        }
+
+        san.push(piece_letter(piece_type));
+        //other pieces of the same kind that could also move to to
+        let rivals = self.pieces_of(piece_type, piece.color()).into_iter()
+            .filter(|pos| *pos != from && self.piece_moves(*pos).contains(&to))
+            .collect::<Vec<_>>();
+        if !rivals.is_empty() {
+            let name = square_name(from);
+            if rivals.iter().all(|pos| pos.1 != from.1) {
+                san.push_str(&name[..1]);
+            } else if rivals.iter().all(|pos| pos.0 != from.0) {
+                san.push_str(&name[1..]);
+            } else {
+                san.push_str(&name);
+            }
+        }
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&square_name(to));
+        san
+    }
+
+    //returns "#" if the side to move is checkmated, "+" if it is in check and "" otherwise
+    pub(crate) fn check_suffix(&self) -> &'static str {
+        if self.is_checkmate(self.turn) {
+            "#"
+        } else if self.is_check(self.turn) {
+            "+"
+        } else {
+            ""
+        }
+    }
+
+    //must be called right before mv changes the board. Saves the position for undo and returns the notation of mv without
+    //the check suffix, neither is needed for the moves of a search
+    pub(crate) fn begin_move(&mut self, mv: Move) -> Option<String> {
+        if !self.recording {
+            return None;
+        }
+        let san = match mv.kind {
+            MoveKind::Drop(piece_type) => drop_san(piece_type, mv.to.pos()),
+            _ => self.san_without_suffix(mv.from.pos(), mv.to.pos(), mv.promotion),
+        };
+        self.save_snapshot();
+        Some(san)
+    }
+
+    //adds a move that was just made to the game's moves, san is what begin_move returned
+    pub(crate) fn finish_move(&mut self, mv: Move, san: Option<String>) {
+        if let Some(san) = san {
+            let suffix = self.check_suffix();
+            self.moves.push(mv);
+            self.san_moves.push(san + suffix);
+        }
+    }
+
+    //makes a move without going through the selection of the caller, promotion must be given for pawns reaching the last rank
+    pub(crate) fn apply_move(&mut self, mv: Move) -> Option<MoveOutcome> {
+        if let MoveKind::Drop(piece_type) = mv.kind {
+            return self.drop_piece(piece_type, mv.to.pos());
+        }
+        self.get_possible_moves(mv.from.pos());
+        match mv.promotion {
+            Some(piece_type) => self.promote_pawn(mv.to.pos(), piece_type),
+            None => self.move_piece(mv.to.pos()),
+        }
+    }
+
+    //returns the positions of all pieces of the given type and color
+    fn pieces_of(&self, piece_type: PieceTypes, color: Color) -> Vec<(i8, i8)> {
+        let mut res = vec![];
+        for (i, row) in self.board.iter().enumerate() {
+            for (j, sqr) in row.iter().enumerate() {
+                if let Some(piece) = sqr {
+                    if piece.piece_type() == piece_type && piece.color() == color {
+                        res.push((i as i8, j as i8));
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+pub(crate) fn piece_letter(piece_type: PieceTypes) -> char {
+    match piece_type {
+        PieceTypes::King => 'K',
+        PieceTypes::Queen => 'Q',
+        PieceTypes::Rook => 'R',
+        PieceTypes::Bishop => 'B',
+        PieceTypes::Knight => 'N',
+        PieceTypes::Pawn => 'P',
+    }
+}
+
+pub(crate) fn letter_piece(letter: char) -> Option<PieceTypes> {
+    match letter {
+        'K' => Some(PieceTypes::King),
+        'Q' => Some(PieceTypes::Queen),
+        'R' => Some(PieceTypes::Rook),
+        'B' => Some(PieceTypes::Bishop),
+        'N' => Some(PieceTypes::Knight),
+        _ => None,
+    }
+}
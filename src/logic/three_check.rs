@@ -0,0 +1,112 @@
+use super::piece::{PieceTypes, Color};
+use super::zobrist::variant_key;
+use super::variant::Variant;
+use super::{LogicManager, GameStatus, Move, Square, FenError};
+use std::any::Any;
+
+//how many checks win the game
+const CHECKS_TO_WIN: u8 = 3;
+
+//giving check for the third time wins the game, besides checkmate
+#[derive(Clone, Default)]
+pub struct ThreeCheck {
+    checks: [u8; 2], //the checks each color gave, black is 0 and white is 1
+}
+
+impl ThreeCheck {
+    //how many times color gave check
+    pub fn checks(&self, color: Color) -> u8 {
+        self.checks[color as usize]
+    }
+}
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn starting_fen(&self) -> String {
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +0+0".to_string()
+    }
+
+    fn after_move(&mut self, lm: &mut LogicManager, _mv: Move, _captured: Option<(Square, PieceTypes)>) {
+        let color = lm.side_to_move();
+        if lm.is_check(color.opposite()) {
+            self.checks[color as usize] += 1;
+        }
+    }
+
+    fn status(&self, _lm: &LogicManager) -> Option<GameStatus> {
+        [Color::White, Color::Black].iter().find(|color| self.checks(**color) >= CHECKS_TO_WIN).map(|color| GameStatus::VariantWin(*color))
+    }
+
+    fn win_reason(&self) -> &'static str {
+        "by three checks"
+    }
+
+    //any piece besides the king can give check
+    fn material_can_draw(&self, lm: &LogicManager) -> bool {
+        lm.get_board().iter().flatten().flatten().all(|piece| piece.piece_type() == PieceTypes::King)
+    }
+
+    //the checks given follow the move counters, white first ("0 1 +1+0"). The remaining checks after the en passant
+    //square ("- 2+3 0 1") are read too
+    fn read_fen(&mut self, fen: &str) -> Result<String, FenError> {
+        let mut fields = fen.split_whitespace().collect::<Vec<_>>();
+        *self = ThreeCheck::default();
+        if let Some(i) = fields.iter().position(|field| field.starts_with('+')) {
+            let field = fields.remove(i);
+            self.checks = parse_checks(&field[1..], field)?;
+            if self.checks.iter().any(|n| *n > CHECKS_TO_WIN) {
+                return Err(FenError::Variant(format!("more than {} checks given in '{}'", CHECKS_TO_WIN, field)));
+            }
+        } else if fields.len() > 4 && fields[4].contains('+') {
+            let field = fields.remove(4);
+            let remaining = parse_checks(field, field)?;
+            if remaining.iter().any(|n| *n > CHECKS_TO_WIN) {
+                return Err(FenError::Variant(format!("more than {} checks remaining in '{}'", CHECKS_TO_WIN, field)));
+            }
+            self.checks = [CHECKS_TO_WIN - remaining[0], CHECKS_TO_WIN - remaining[1]];
+        }
+        Ok(fields.join(" "))
+    }
+
+    fn write_fen(&self, fen: String) -> String {
+        format!("{} +{}+{}", fen, self.checks(Color::White), self.checks(Color::Black))
+    }
+
+    fn state_text(&self) -> Option<String> {
+        Some(format!("Checks\nWhite: {}\nBlack: {}", self.checks(Color::White), self.checks(Color::Black)))
+    }
+
+    //the king in check
+    fn marked_squares(&self, lm: &LogicManager) -> Vec<(i8, i8)> {
+        let color = lm.side_to_move();
+        if lm.is_check(color) {
+            vec![lm.king_pos(color)]
+        } else {
+            vec![]
+        }
+    }
+
+    fn hash(&self) -> u64 {
+        variant_key(self.checks[0] as u64) ^ variant_key(100 + self.checks[1] as u64)
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+//parses "N+M" into the counts of black and white, where N belongs to white
+fn parse_checks(checks: &str, field: &str) -> Result<[u8; 2], FenError> {
+    let parsed = checks.split_once('+').and_then(|(white, black)| Some([black.parse::<u8>().ok()?, white.parse::<u8>().ok()?]));
+    match parsed {
+        Some(parsed) => Ok(parsed),
+        None => Err(FenError::Variant(format!("'{}' are not the checks of three-check", field))),
+    }
+}
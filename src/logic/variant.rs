@@ -0,0 +1,117 @@
+use super::piece::{PieceTypes, Color};
+use super::bitboard::Bitboards;
+use super::{LogicManager, GameStatus, Move, Square, FenError, STARTING_FEN};
+use std::any::Any;
+pub use super::crazyhouse::Crazyhouse;
+pub use super::three_check::ThreeCheck;
+pub use super::king_of_the_hill::KingOfTheHill;
+pub use super::atomic::Atomic;
+
+//the rules a game is played by, on top of the moves of the pieces. Every hook defaults to standard chess,
+//so a variant only implements the rules it changes
+pub trait Variant: Send + Sync {
+    //the name written in the Variant tag of PGN files
+    fn name(&self) -> &'static str;
+    //the position games of the variant start from, in Forsyth-Edwards Notation
+    fn starting_fen(&self) -> String {
+        STARTING_FEN.to_string()
+    }
+    //the squares the piece at from may move to, for variants that decide themselves which moves are legal, starting
+    //from LogicManager::pseudo_legal_moves. None keeps the standard rule that a move may not leave the own king in check
+    fn piece_moves(&self, _lm: &LogicManager, _from: (i8, i8)) -> Option<Vec<(i8, i8)>> {
+        None
+    }
+    //true if the king of color is in check in position
+    fn is_check(&self, position: &Bitboards, color: Color) -> bool {
+        let king = position.pieces(PieceTypes::King, color);
+        king != 0 && position.is_attacked(king.trailing_zeros() as usize, color.opposite())
+    }
+    //removes moves the variant forbids from the moves of the piece at from, which follow the standard rules
+    fn filter_moves(&self, _lm: &LogicManager, _from: (i8, i8), moves: Vec<(i8, i8)>) -> Vec<(i8, i8)> {
+        moves
+    }
+    //called after a move changed the board and before the turn passes, so the variant can change the board further or
+    //update its own state. captured is the square and type of the piece the move took
+    fn after_move(&mut self, _lm: &mut LogicManager, _mv: Move, _captured: Option<(Square, PieceTypes)>) {}
+    //ends the game by a rule of the variant, checked before the standard rules. None leaves it to the standard rules
+    fn status(&self, _lm: &LogicManager) -> Option<GameStatus> {
+        None
+    }
+    //how a game won by a rule of the variant was won, completing "White wins ..."
+    fn win_reason(&self) -> &'static str {
+        "by the rules of the variant"
+    }
+    //false if the game can still be won although the pieces on the board could not checkmate, which turns off the
+    //insufficient material draw
+    fn material_can_draw(&self, _lm: &LogicManager) -> bool {
+        true
+    }
+    //the pieces a pawn reaching the last rank may promote into
+    fn promotions(&self) -> Vec<PieceTypes> {
+        vec![PieceTypes::Queen, PieceTypes::Rook, PieceTypes::Bishop, PieceTypes::Knight]
+    }
+    //the pieces color holds in hand and may drop on the board, with how many of each. None for variants without drops
+    fn pocket(&self, _color: Color) -> Option<Vec<(PieceTypes, u8)>> {
+        None
+    }
+    //reads the state of the variant that a FEN carries besides the standard fields, and returns the FEN without it
+    fn read_fen(&mut self, fen: &str) -> Result<String, FenError> {
+        Ok(fen.to_string())
+    }
+    //adds the state of the variant to a standard FEN of the position
+    fn write_fen(&self, fen: String) -> String {
+        fen
+    }
+    //the state of the variant that players should see beside the board, like the checks given in three-check
+    fn state_text(&self) -> Option<String> {
+        None
+    }
+    //the squares that front-ends should mark on the board in the current position
+    fn marked_squares(&self, _lm: &LogicManager) -> Vec<(i8, i8)> {
+        vec![]
+    }
+    //the part of the position hash for the state of the variant, so positions only repeat if that state is the same too
+    fn hash(&self) -> u64 {
+        0
+    }
+    //must be implemented for cloning of Variant
+    fn box_clone(&self) -> Box<dyn Variant>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl Clone for Box<dyn Variant> {
+    fn clone(&self) -> Box<dyn Variant> {
+        self.box_clone()
+    }
+}
+
+//standard chess, the default rules
+#[derive(Clone, Default)]
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "Standard"
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+//creates the variant with the given name, as written in the Variant tag of PGN files. Returns None for unknown variants
+pub fn variant_from_name(name: &str) -> Option<Box<dyn Variant>> {
+    match name.to_ascii_lowercase().as_str() {
+        "standard" | "" => Some(Box::new(Standard)),
+        "crazyhouse" => Some(Box::new(Crazyhouse::default())),
+        "three-check" | "threecheck" | "3check" => Some(Box::new(ThreeCheck::default())),
+        "king of the hill" | "king-of-the-hill" | "kingofthehill" | "koth" => Some(Box::new(KingOfTheHill)),
+        "atomic" => Some(Box::new(Atomic)),
+        _ => None,
+    }
+}
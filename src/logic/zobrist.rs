@@ -0,0 +1,126 @@
+use super::LogicManager;
+use super::piece::{PieceTypes, Color};
+use super::bitboard::square;
+
+//random numbers for every feature of a position, the key of a position is all of its features xored together
+struct ZobristKeys {
+    pieces: [[[u64; 64]; 6]; 2], //indexed by color, black is 0 and white is 1, then by PieceTypes and square
+    castling: [u64; 4], //white kingside, white queenside, black kingside, black queenside
+    en_passant: [u64; 8], //indexed by file
+    black_to_move: u64,
+}
+
+const KEYS: ZobristKeys = generate_keys();
+
+//splitmix64, returns the next random number and the new state. A fixed seed keeps keys equal between runs
+const fn next_random(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31), state)
+}
+
+const fn generate_keys() -> ZobristKeys {
+    let mut keys = ZobristKeys { pieces: [[[0; 64]; 6]; 2], castling: [0; 4], en_passant: [0; 8], black_to_move: 0 };
+    let mut state = 0x2545_F491_4F6C_DD1D;
+    let mut color = 0;
+    while color < 2 {
+        let mut piece_type = 0;
+        while piece_type < 6 {
+            let mut sqr = 0;
+            while sqr < 64 {
+                let (key, next) = next_random(state);
+                keys.pieces[color][piece_type][sqr] = key;
+                state = next;
+                sqr += 1;
+            }
+            piece_type += 1;
+        }
+        color += 1;
+    }
+    let mut i = 0;
+    while i < 4 {
+        let (key, next) = next_random(state);
+        keys.castling[i] = key;
+        state = next;
+        i += 1;
+    }
+    i = 0;
+    while i < 8 {
+        let (key, next) = next_random(state);
+        keys.en_passant[i] = key;
+        state = next;
+        i += 1;
+    }
+    keys.black_to_move = next_random(state).0;
+    keys
+}
+
+//a random key for the state of a variant, the same index always gives the same key
+pub(crate) fn variant_key(index: u64) -> u64 {
+    next_random(0x5851_F42D_4C95_7F2D ^ index).0
+}
+
+impl LogicManager {
+    //the 64 bit Zobrist hash of the position, updated with every move. Two positions that are the same under the
+    //repetition rules have the same hash: the same pieces, side to move, castling rights, possible en passant capture
+    //and state of the variant
+    pub fn position_hash(&self) -> u64 {
+        self.hash
+    }
+
+    //computes the hash of the position from scratch
+    pub(crate) fn compute_hash(&self) -> u64 {
+        let mut hash = self.state_hash();
+        for (i, row) in self.board.iter().enumerate() {
+            for (j, sqr) in row.iter().enumerate() {
+                if let Some(piece) = sqr {
+                    hash ^= KEYS.pieces[piece.color() as usize][piece.piece_type() as usize][square((i as i8, j as i8))];
+                }
+            }
+        }
+        hash
+    }
+
+    //the part of the hash that does not depend on where the pieces stand. A move removes it from the hash
+    //before changing anything, and adds it back once the move is done
+    pub(crate) fn state_hash(&self) -> u64 {
+        let mut hash = 0;
+        for (i, (color, kingside)) in [(Color::White, true), (Color::White, false), (Color::Black, true), (Color::Black, false)].iter().enumerate() {
+            if self.castling_rook_col(*color, *kingside).is_some() {
+                hash ^= KEYS.castling[i];
+            }
+        }
+        if let Some(pos) = self.en_passant {
+            if self.can_capture_en_passant(pos) {
+                hash ^= KEYS.en_passant[pos.1 as usize];
+            }
+        }
+        if self.turn == Color::Black {
+            hash ^= KEYS.black_to_move;
+        }
+        hash ^ self.variant.hash()
+    }
+
+    //adds the piece at pos to the hash, or removes it if it was already added. Does nothing for an empty square
+    pub(crate) fn toggle_piece_hash(&mut self, pos: (i8, i8)) {
+        if let Some(piece) = self.board[pos.0 as usize][pos.1 as usize].as_ref() {
+            self.hash ^= KEYS.pieces[piece.color() as usize][piece.piece_type() as usize][square(pos)];
+        }
+    }
+
+    //true if a pawn of the side to move can legally capture on the en passant square pos
+    fn can_capture_en_passant(&self, pos: (i8, i8)) -> bool {
+        let row = match self.turn {
+            Color::White => pos.0 + 1,
+            Color::Black => pos.0 - 1,
+        };
+        [pos.1 - 1, pos.1 + 1].iter().any(|col| {
+            (0..8).contains(col) &&
+                self.board[row as usize][*col as usize].as_ref()
+                    .is_some_and(|piece| piece.piece_type() == PieceTypes::Pawn && piece.color() == self.turn) &&
+                self.piece_moves((row, *col)).contains(&pos)
+        })
+    }
+}
@@ -1,28 +1,102 @@
-pub mod logic;
-pub mod gui;
-
-use logic::piece::{Board};
-use logic::LogicManager;
-use gui::*;
+use chess::logic::{LogicManager, Color as PieceColor};
+use chess::logic::variant::{Variant, Standard, variant_from_name};
+use chess::gui::*;
+use chess::{cli, uci};
 use bevy_prototype_lyon::plugin::ShapePlugin;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--cli") {
+        cli::run();
+        return;
+    }
+    if args.iter().any(|arg| arg == "--uci") {
+        uci::run();
+        return;
+    }
+    //the engine plays the color the player did not choose
+    let engine_color = match args.iter().position(|arg| arg == "--play-as").map(|i| args.get(i + 1).map(String::as_str)) {
+        Some(Some("white")) => Some(PieceColor::Black),
+        Some(Some("black")) => Some(PieceColor::White),
+        Some(_) => {
+            eprintln!("Usage: --play-as white|black");
+            return;
+        }
+        None => None,
+    };
+    //--variant plays a new game of the variant with the given name
+    let variant: Box<dyn Variant> = match args.iter().position(|arg| arg == "--variant").map(|i| args.get(i + 1).and_then(|name| variant_from_name(name))) {
+        Some(Some(variant)) => variant,
+        Some(None) => {
+            eprintln!("Usage: --variant standard|crazyhouse|three-check|king-of-the-hill|atomic");
+            return;
+        }
+        None => Box::new(Standard),
+    };
+    //--resume continues the game that was being played when the window was last closed, unless it was finished
+    let lm = if args.iter().any(|arg| arg == "--resume") {
+        match LogicManager::load_pgn(AUTOSAVE_FILE) {
+            Ok(lm) if !lm.status().is_over() => lm,
+            Ok(_) => {
+                println!("The last game is over, starting a new one");
+                LogicManager::new()
+            }
+            Err(e) => {
+                eprintln!("Could not resume the last game: {}", e);
+                LogicManager::new()
+            }
+        }
+    } else if let Some(i) = args.iter().position(|arg| arg == "--fen") {
+        //--fen starts from any position, given as a single argument
+        match args.get(i + 1).map(|fen| LogicManager::from_fen_with_variant(fen, variant)) {
+            Some(Ok(lm)) => lm,
+            Some(Err(e)) => {
+                eprintln!("Invalid FEN: {}", e);
+                return;
+            }
+            None => {
+                eprintln!("Usage: --fen \"<FEN>\"");
+                return;
+            }
+        }
+    } else if let Some(i) = args.iter().position(|arg| arg == "--chess960") {
+        //--chess960 starts from the Chess960 start position with the given number, or from a random one
+        let number = match args.get(i + 1).and_then(|number| number.parse::<u16>().ok()) {
+            Some(number) => number,
+            None => (SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos() % 960) as u16,
+        };
+        match LogicManager::new_chess960(number) {
+            Some(lm) => {
+                println!("Chess960 start position {}", number);
+                lm
+            }
+            None => {
+                eprintln!("Usage: --chess960 [0-959]");
+                return;
+            }
+        }
+    } else {
+        LogicManager::with_variant(variant)
+    };
+
     App::build()
         .insert_resource(WindowDescriptor {
             title: "Chess".to_string(),
-            width: SCREEN_HEIGHT,
+            width: window_width(&lm),
             height: SCREEN_HEIGHT,
             resizable: false,
             ..Default::default()
         })
-        .insert_resource(LogicManager::new())
-        .insert_resource(Turn(true))
+        .insert_resource(lm)
+        .insert_resource(EngineOpponent::new(engine_color))
         .insert_resource(Capture(false))
         .insert_resource(Moved(false))
         .insert_resource(PromotePawnOption {
             happened: false,
             new_pos: (-1, -1),
         })
+        .insert_resource(PocketSelection(None))
         .insert_resource(ClearColor(Color::BLACK))
         //.insert_resource(Msaa { samples: 4} )
         //.add_state(AppState::CreateWindow)
@@ -33,60 +107,30 @@ fn main() {
         .add_stage_after(StageLabels::MouseClicks, StageLabels::MoveCalculation, SystemStage::single_threaded())
         .add_stage_after(StageLabels::MoveCalculation, StageLabels::AfterTurnUpdates, SystemStage::single_threaded())
         .add_stage_after(StageLabels::AfterTurnUpdates, StageLabels::PositionCalculation, SystemStage::single_threaded())
+        .add_system(undo_redo.system())
+        .add_system(claim_draw.system())
+        .add_system(save_load.system())
+        .add_system(autosave.system())
         .add_system_to_stage(StageLabels::MouseClicks, mouse_clicks.system())
+        .add_system_to_stage(StageLabels::MouseClicks, engine_turn.system())
         .add_system_to_stage(StageLabels::MoveCalculation, piece_options.system())
         .add_system_to_stage(StageLabels::MoveCalculation, move_piece.system())
         .add_system_to_stage(StageLabels::MoveCalculation, promote_pawn_choice.system())
+        .add_system_to_stage(StageLabels::MoveCalculation, drop_piece.system())
         .add_system_to_stage(StageLabels::AfterTurnUpdates, after_turn_updates.system())
+        .add_system_to_stage(StageLabels::AfterTurnUpdates, resync_pieces.system())
+        .add_system_to_stage(StageLabels::AfterTurnUpdates, update_variant_marks.system())
+        .add_system_to_stage(StageLabels::AfterTurnUpdates, explosions.system())
         .add_system_to_stage(StageLabels::PositionCalculation, position_translation.system())
+        .add_system_to_stage(StageLabels::PositionCalculation, update_pockets.system())
+        .add_system_to_stage(StageLabels::PositionCalculation, animate_explosions.system())
         .add_plugins(DefaultPlugins)
         .add_plugin(ShapePlugin)
         .add_plugin(bevy_kira_audio::AudioPlugin)
         .add_event::<PieceOptionEvent>()
         .add_event::<MoveEvent>()
         .add_event::<PawnPromotionEvent>()
+        .add_event::<DropEvent>()
+        .add_event::<ExplosionEvent>()
         .run();
 }
-
-fn cli_chess() {
-    let mut lm = LogicManager::new();
-    let mut buf = String::new();
-    let mut turn = true;
-    loop {
-        print_board_ascii(lm.get_board());
-        println!("What would you like to move?");
-        buf.clear();
-        match std::io::stdin().read_line(&mut buf){
-            Ok(_) => (),
-            Err(e) => panic!("Error occurred: {:?}", e),
-        }
-        let poss_moves = lm.get_possible_moves((56 - buf.as_bytes()[1] as i8, buf.as_bytes()[0] as i8 - 97));
-        println!("{:?}", poss_moves);
-        println!("where would you like to move?");
-        buf.clear();
-        match std::io::stdin().read_line(&mut buf){
-            Ok(_) => (),
-            Err(e) => panic!("Error occurred: {:?}", e),
-        }
-        if buf.as_bytes().len() < 2 || !poss_moves.unwrap().contains(&(56 - buf.as_bytes()[1] as i8, buf.as_bytes()[0] as i8 - 97)) {
-            println!("not possible move");
-            continue;
-        }
-        lm.move_piece((56 - buf.as_bytes()[1] as i8, buf.as_bytes()[0] as i8 - 97));
-        println!("is check: {}\nis checkmate: {}", lm.is_check(turn), lm.is_checkmate(turn));
-        turn = !turn;
-    }
-}
-
-fn print_board_ascii(board: &Board) {
-    for row in board {
-        for sqr in row {
-            match &sqr {
-                Some(p) => p.print(),
-                None => print!("{}", "."),
-            }
-        }
-        println!();
-    }
-    println!();
-}
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::engine::{Engine, SearchInfo, SearchLimits};
+use crate::logic::{LogicManager, Color, STARTING_FEN};
+use std::io::{self, BufRead};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+//moves left in the game assumed when the GUI does not send movestogo
+const DEFAULT_MOVES_TO_GO: i64 = 30;
+//time kept in reserve so the engine does not lose on time because of the communication delay
+const MOVE_OVERHEAD_MS: i64 = 50;
+
+//a search running in the background, stopped through the flag
+struct Search {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+//speaks the Universal Chess Interface over stdin and stdout, so the engine can be used from chess GUIs
+pub fn run() {
+    let mut lm = LogicManager::new();
+    let mut chess960 = false;
+    let mut search = None;
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("uci") => {
+                println!("id name chess");
+                println!("id author Gderu");
+                println!("option name UCI_Chess960 type check default false");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("setoption") => {
+                let words = words.collect::<Vec<_>>();
+                if words.get(1).is_some_and(|name| name.eq_ignore_ascii_case("UCI_Chess960")) {
+                    chess960 = words.get(3) == Some(&"true");
+                }
+            }
+            Some("ucinewgame") => {
+                stop_search(&mut search);
+                lm = start_position(chess960);
+            }
+            Some("position") => {
+                stop_search(&mut search);
+                match parse_position(&words.collect::<Vec<_>>(), chess960) {
+                    Ok(position) => lm = position,
+                    Err(e) => println!("info string {}", e),
+                }
+            }
+            Some("go") => {
+                stop_search(&mut search);
+                let words = words.collect::<Vec<_>>();
+                search = Some(start_search(&lm, go_limits(&words, lm.side_to_move()), words.contains(&"infinite")));
+            }
+            Some("stop") => stop_search(&mut search),
+            Some("quit") => {
+                stop_search(&mut search);
+                break;
+            }
+            //unknown commands are ignored, as the protocol asks
+            _ => (),
+        }
+    }
+}
+
+//the standard starting position. With UCI_Chess960 castling is sent as the king moving onto its rook, so it is set up as Chess960
+fn start_position(chess960: bool) -> LogicManager {
+    if chess960 {
+        LogicManager::from_fen_chess960(STARTING_FEN).unwrap()
+    } else {
+        LogicManager::new()
+    }
+}
+
+//parses the arguments of "position": startpos or fen <fen>, optionally followed by moves <move>...
+fn parse_position(words: &[&str], chess960: bool) -> Result<LogicManager, String> {
+    let moves_start = words.iter().position(|word| *word == "moves").unwrap_or(words.len());
+    let mut lm = match words.first() {
+        Some(&"startpos") => start_position(chess960),
+        Some(&"fen") => {
+            let fen = words[1..moves_start].join(" ");
+            if chess960 { LogicManager::from_fen_chess960(&fen) } else { LogicManager::from_fen(&fen) }.map_err(|e| e.to_string())?
+        }
+        _ => return Err("expected startpos or fen".to_string()),
+    };
+    for word in words.iter().skip(moves_start + 1) {
+        let mv = lm.parse_san(word).map_err(|e| e.to_string())?;
+        lm.make_move(mv).map_err(|e| e.to_string())?;
+    }
+    Ok(lm)
+}
+
+//turns the arguments of "go" into search limits. Without any limit the engine searches until it is stopped
+fn go_limits(words: &[&str], side_to_move: Color) -> SearchLimits {
+    let value = |name: &str| words.iter().position(|word| *word == name)
+        .and_then(|i| words.get(i + 1))
+        .and_then(|value| value.parse::<i64>().ok());
+
+    let mut limits = SearchLimits { depth: u32::MAX, time: None };
+    if let Some(depth) = value("depth") {
+        limits.depth = depth.max(1) as u32;
+    }
+    let (time, increment) = match side_to_move {
+        Color::White => (value("wtime"), value("winc")),
+        Color::Black => (value("btime"), value("binc")),
+    };
+    if let Some(movetime) = value("movetime") {
+        limits.time = Some(Duration::from_millis(movetime.max(1) as u64));
+    } else if let Some(time) = time {
+        let moves_to_go = value("movestogo").unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let budget = time / moves_to_go + increment.unwrap_or(0) / 2;
+        limits.time = Some(Duration::from_millis(budget.min(time - MOVE_OVERHEAD_MS).max(1) as u64));
+    }
+    limits
+}
+
+fn start_search(lm: &LogicManager, limits: SearchLimits, infinite: bool) -> Search {
+    let stop = Arc::new(AtomicBool::new(false));
+    let position = lm.clone();
+    let thread_stop = stop.clone();
+    let handle = thread::spawn(move || {
+        let best = Engine::search(&position, limits, thread_stop.clone(), print_info);
+        //when searching infinitely the best move may only be sent after the GUI says stop
+        while infinite && !thread_stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(5));
+        }
+        match best {
+            Some(mv) => println!("bestmove {}", mv),
+            None => println!("bestmove 0000"),
+        }
+    });
+    Search { stop, handle }
+}
+
+//stops the search in progress, if any, and waits for it to send its best move
+fn stop_search(search: &mut Option<Search>) {
+    if let Some(search) = search.take() {
+        search.stop.store(true, Ordering::Relaxed);
+        let _ = search.handle.join();
+    }
+}
+
+fn print_info(info: &SearchInfo) {
+    let score = match info.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", info.score),
+    };
+    let millis = info.time.as_millis() as u64;
+    let pv = info.pv.iter().map(|mv| mv.to_uci()).collect::<Vec<_>>().join(" ");
+    println!("info depth {} score {} nodes {} time {} nps {} pv {}",
+             info.depth, score, info.nodes, millis, info.nodes * 1000 / millis.max(1), pv);
+}
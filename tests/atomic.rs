@@ -0,0 +1,108 @@
+use chess::logic::{LogicManager, GameStatus, Square, Color};
+use chess::logic::variant::{Atomic, variant_from_name};
+use chess::logic::piece::PieceTypes;
+use chess::logic::pgn::Pgn;
+use std::convert::TryFrom;
+
+mod common;
+use common::{play, variant_game};
+
+#[test]
+fn move_generation() {
+    //node counts of the start position in atomic chess, which differ from standard chess from depth 4
+    let mut lm = LogicManager::with_variant(Box::new(Atomic));
+    assert_eq!(lm.perft(3), 8902);
+    assert_eq!(lm.variant().name(), "Atomic");
+}
+
+#[test]
+fn captures_explode() {
+    let mut lm = variant_game::<Atomic>("4k3/8/8/2nrp3/8/4N3/8/4K3 w - - 0 1");
+    let mv = lm.parse_san("Nxd5").unwrap();
+    let outcome = lm.make_move(mv).unwrap();
+    //the knights and the rook explode, the pawn next to them survives
+    assert_eq!(lm.to_fen(), "4k3/8/8/4p3/8/8/8/4K3 b - - 0 1");
+    assert_eq!(outcome.captured, Some((Square::new(3, 3), PieceTypes::Rook)));
+    let mut removed = outcome.removed;
+    removed.sort_unstable_by_key(|(sqr, _piece_type)| sqr.col);
+    assert_eq!(removed, vec![(Square::new(3, 2), PieceTypes::Knight), (Square::new(3, 3), PieceTypes::Knight)]);
+    //the position is hashed like the same position set up directly
+    assert_eq!(lm.position_hash(), variant_game::<Atomic>(&lm.to_fen()).position_hash());
+    assert!(lm.undo());
+    assert_eq!(lm.to_fen(), "4k3/8/8/2nrp3/8/4N3/8/4K3 w - - 0 1");
+
+    //moves without a capture explode nothing
+    let outcome = LogicManager::with_variant(Box::new(Atomic)).make_move(LogicManager::new().parse_san("e4").unwrap()).unwrap();
+    assert!(outcome.removed.is_empty());
+
+    //capturing en passant explodes around the square the pawn moves to
+    let mut lm = variant_game::<Atomic>("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    play(&mut lm, "exd6");
+    assert_eq!(lm.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn kings_can_not_capture() {
+    let lm = variant_game::<Atomic>("4k3/8/8/8/8/8/4p3/4K3 w - - 0 1");
+    assert!(lm.parse_san("Kxe2").is_err());
+    assert!(lm.parse_san("Kd2").is_ok());
+}
+
+#[test]
+fn exploding_the_king_wins() {
+    let mut lm = LogicManager::with_variant(variant_from_name("atomic").unwrap());
+    play(&mut lm, "Nf3 d5 Ng5 e6");
+    let mv = lm.parse_san("Nxf7").unwrap();
+    let outcome = lm.make_move(mv).unwrap();
+    assert!(outcome.removed.iter().any(|(sqr, piece_type)| *sqr == Square::new(0, 4) && *piece_type == PieceTypes::King));
+    assert_eq!(lm.status(), GameStatus::VariantWin(Color::White));
+    assert_eq!(outcome.san, "Nxf7#");
+    assert_eq!(lm.variant().win_reason(), "by exploding the king");
+    assert!(lm.legal_moves().is_empty());
+    assert_eq!(lm.to_fen(), "rnbq3r/ppp3pp/4p3/3p4/8/8/PPPPPPPP/RNBQKB1R b KQ - 0 3");
+
+    let pgn = Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("Variant"), Some("Atomic"));
+    assert_eq!(pgn.result, "1-0");
+    let replayed = Pgn::parse(&pgn.to_string()).unwrap().replay().unwrap();
+    assert_eq!(replayed.to_fen(), lm.to_fen());
+    assert_eq!(LogicManager::try_from(lm.to_record()).unwrap().status(), GameStatus::VariantWin(Color::White));
+
+    //a move that explodes the other king is legal even though it leaves the own king attacked
+    let lm = variant_game::<Atomic>("4k3/3p4/8/8/8/8/4r3/3QK3 w - - 0 1");
+    assert!(lm.is_check(Color::White));
+    assert!(lm.parse_san("Qxd7").is_ok());
+}
+
+#[test]
+fn moves_may_not_explode_the_own_king() {
+    let lm = variant_game::<Atomic>("4k3/8/8/8/8/8/3pP3/3QK3 w - - 0 1");
+    assert!(lm.parse_san("Qxd2").is_err());
+    assert!(lm.parse_san("Kxd2").is_err());
+}
+
+#[test]
+fn touching_kings_are_not_in_check() {
+    let lm = variant_game::<Atomic>("8/8/8/8/3kK3/8/8/7r w - - 0 1");
+    assert!(!lm.is_check(Color::White));
+    assert!(variant_game::<Atomic>("8/8/8/3k4/8/8/8/4K2r w - - 0 1").is_check(Color::White));
+    //the white king may step next to the black king although the rook attacks that square
+    let lm = variant_game::<Atomic>("8/8/8/3k4/7r/4K3/8/8 w - - 0 1");
+    assert!(lm.parse_san("Ke4").is_ok());
+    assert!(lm.parse_san("Kd4").is_ok());
+    assert!(lm.parse_san("Kf4").is_err());
+}
+
+#[test]
+fn material() {
+    assert_eq!(variant_game::<Atomic>("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").status(), GameStatus::InsufficientMaterial);
+    assert_eq!(variant_game::<Atomic>("4k3/4b3/8/8/8/8/8/4KB2 w - - 0 1").status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn searches_put_exploded_pieces_back() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let mut lm = variant_game::<Atomic>(fen);
+    lm.perft(3);
+    assert_eq!(lm.to_fen(), variant_game::<Atomic>(fen).to_fen());
+}
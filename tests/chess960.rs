@@ -0,0 +1,140 @@
+use chess::logic::{LogicManager, Move, MoveKind, Square, chess960_back_rank, chess960_fen, STARTING_FEN};
+use chess::logic::piece::PieceTypes;
+
+fn sqr(name: &str) -> Square {
+    Square::from_name(name).unwrap()
+}
+
+//node counts from https://www.chessprogramming.org/Chess960_Perft_Results
+fn check_perft(fen: &str, expected: &[u64]) {
+    let mut lm = LogicManager::from_fen_chess960(fen).unwrap();
+    for (depth, nodes) in expected.iter().enumerate() {
+        assert_eq!(lm.perft(depth as u32 + 1), *nodes, "perft({}) of {}", depth + 1, fen);
+    }
+    assert_eq!(lm.to_shredder_fen(), fen, "perft changed the position");
+}
+
+#[test]
+fn perft() {
+    check_perft("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", &[21, 528, 12189]);
+    check_perft("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", &[21, 807, 18002]);
+    check_perft("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", &[20, 479, 10471]);
+    check_perft("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9", &[22, 593, 13440]);
+    check_perft("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9", &[28, 1120, 31058]);
+    check_perft("qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9", &[29, 899, 26578]);
+}
+
+#[test]
+fn start_positions() {
+    use PieceTypes::*;
+    assert_eq!(chess960_back_rank(518), Some([Rook, Knight, Bishop, Queen, King, Bishop, Knight, Rook]));
+    assert_eq!(chess960_back_rank(0), Some([Bishop, Bishop, Queen, Knight, Knight, Rook, King, Rook]));
+    assert_eq!(chess960_back_rank(959), Some([Rook, King, Rook, Knight, Knight, Queen, Bishop, Bishop]));
+    assert_eq!(chess960_back_rank(960), None);
+    assert_eq!(chess960_fen(518).unwrap(), STARTING_FEN);
+
+    let mut seen = std::collections::HashSet::new();
+    for number in 0..960 {
+        let rank = chess960_back_rank(number).unwrap();
+        assert!(seen.insert(chess960_fen(number).unwrap()), "position {} repeats", number);
+        let cols = |piece_type| (0..8).filter(|col| rank[*col] == piece_type).collect::<Vec<_>>();
+        let (bishops, rooks, king) = (cols(Bishop), cols(Rook), cols(King)[0]);
+        assert_ne!(bishops[0] % 2, bishops[1] % 2, "bishops of position {} on the same color", number);
+        assert!(rooks[0] < king && king < rooks[1], "king of position {} is not between the rooks", number);
+        let lm = LogicManager::new_chess960(number).unwrap();
+        assert_eq!(lm.to_fen(), chess960_fen(number).unwrap());
+    }
+}
+
+#[test]
+fn castling_moves_onto_the_rook() {
+    //the king stays on g1 and the rook comes from h1 to f1
+    let mut lm = LogicManager::from_fen_chess960("4k3/8/8/8/8/8/8/1R4KR w HB - 0 1").unwrap();
+    let castle = Move::new(sqr("g1"), sqr("h1"), None, MoveKind::Castle);
+    assert!(lm.legal_moves().contains(&castle));
+    assert_eq!(castle.destination(), sqr("g1"));
+    assert!(!castle.is_capture(&lm));
+    let outcome = lm.make_move(castle).unwrap();
+    assert_eq!(outcome.san, "O-O");
+    assert_eq!(outcome.captured, None);
+    assert_eq!(outcome.rook_move, Some((sqr("h1"), sqr("f1"))));
+    assert_eq!(lm.to_fen(), "4k3/8/8/8/8/8/8/1R3RK1 b - - 1 1");
+
+    //the rook lands where the king stood
+    let mut lm = LogicManager::from_fen_chess960("4k3/8/8/8/8/8/8/1R1K4 w B - 0 1").unwrap();
+    let castle = lm.parse_san("O-O-O").unwrap();
+    assert_eq!(castle, Move::new(sqr("d1"), sqr("b1"), None, MoveKind::Castle));
+    assert_eq!(castle.destination(), sqr("c1"));
+    lm.make_move(castle).unwrap();
+    assert_eq!(lm.to_fen(), "4k3/8/8/8/8/8/8/2KR4 b - - 1 1");
+    assert!(lm.undo());
+    assert_eq!(lm.to_shredder_fen(), "4k3/8/8/8/8/8/8/1R1K4 w B - 0 1");
+}
+
+#[test]
+fn castling_rules() {
+    let castles = |fen: &str| {
+        let lm = LogicManager::from_fen_chess960(fen).unwrap();
+        lm.legal_moves().into_iter().filter(|mv| mv.kind == MoveKind::Castle).count()
+    };
+    assert_eq!(castles("4k3/8/8/8/8/8/8/RK4R1 w GA - 0 1"), 2);
+    //a piece on a square only the rook passes
+    assert_eq!(castles("4k3/8/8/8/8/8/8/RN3K1R w HA - 0 1"), 1);
+    //a piece on the rook's destination
+    assert_eq!(castles("4k3/8/8/8/8/8/8/RK3NR1 w GA - 0 1"), 1);
+    //a piece on the king's destination
+    assert_eq!(castles("4k3/8/8/8/8/8/8/R2K2NR w HA - 0 1"), 1);
+    //the king passes an attacked square
+    assert_eq!(castles("4k3/8/8/8/8/4r3/8/RK4R1 w GA - 0 1"), 1);
+    //the king would be in check on c1 once the rook leaves b1, even though the square is not attacked now
+    assert_eq!(castles("4k3/8/8/8/8/8/8/qRK5 w B - 0 1"), 0);
+    //no castling out of check
+    assert_eq!(castles("4k3/8/8/8/8/8/1r6/RK4R1 w GA - 0 1"), 0);
+}
+
+#[test]
+fn castling_rights_in_fen() {
+    //X-FEN uses the file only when another rook stands further out
+    let lm = LogicManager::from_fen_chess960("rk2r2r/8/8/8/8/8/8/RK2R2R w HAe - 0 1").unwrap();
+    assert_eq!(lm.to_fen(), "rk2r2r/8/8/8/8/8/8/RK2R2R w KQe - 0 1");
+    assert_eq!(lm.to_shredder_fen(), "rk2r2r/8/8/8/8/8/8/RK2R2R w HAe - 0 1");
+    assert_eq!(LogicManager::from_fen_chess960(&lm.to_fen()).unwrap().to_shredder_fen(), lm.to_shredder_fen());
+    //a rook that moved away and back has given up its right
+    let mut lm = LogicManager::from_fen_chess960("4k3/8/8/8/8/8/8/RK4R1 w GA - 0 1").unwrap();
+    for san in ["Rg2", "Ke7", "Rg1"] {
+        lm.make_move(lm.parse_san(san).unwrap()).unwrap();
+    }
+    assert_eq!(lm.to_shredder_fen(), "8/4k3/8/8/8/8/8/RK4R1 b A - 3 2");
+    assert_eq!(LogicManager::new_chess960(0).unwrap().to_shredder_fen(), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1");
+
+    for fen in ["4k3/8/8/8/8/8/8/RK4R1 w C - 0 1", "4k3/8/8/8/8/8/8/RK4R1 w GH - 0 1", "4k3/8/8/8/8/8/8/RK4R1 w GK - 0 1"] {
+        assert!(LogicManager::from_fen_chess960(fen).is_err(), "{}", fen);
+    }
+    //standard FEN keeps its rules
+    assert!(LogicManager::from_fen("4k3/8/8/8/8/8/8/RK4R1 w GA - 0 1").is_err());
+}
+
+#[test]
+fn standard_castling_is_unchanged() {
+    let mut lm = LogicManager::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert!(!lm.is_chess960());
+    let castle = lm.parse_san("O-O").unwrap();
+    assert_eq!(castle, Move::new(sqr("e1"), sqr("g1"), None, MoveKind::Castle));
+    assert_eq!(castle.destination(), sqr("g1"));
+    assert_eq!(castle.to_uci(), "e1g1");
+    lm.make_move(castle).unwrap();
+    assert_eq!(lm.to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+}
+
+#[test]
+fn saved_games_stay_chess960() {
+    let mut lm = LogicManager::from_fen_chess960("rk4r1/pppppppp/8/8/8/8/PPPPPPPP/RK4R1 w GAga - 0 1").unwrap();
+    for san in ["O-O", "O-O-O", "Rfe1"] {
+        lm.make_move(lm.parse_san(san).unwrap()).unwrap();
+    }
+    let pgn = chess::logic::pgn::Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("Variant"), Some("Chess960"));
+    let replayed = chess::logic::pgn::Pgn::parse(&pgn.to_string()).unwrap().replay().unwrap();
+    assert!(replayed.is_chess960());
+    assert_eq!(replayed.to_fen(), lm.to_fen());
+}
@@ -0,0 +1,18 @@
+//helpers shared by the integration tests, not every test file uses all of them
+#![allow(dead_code)]
+
+use chess::logic::LogicManager;
+use chess::logic::variant::Variant;
+
+//plays moves written in algebraic notation and separated by spaces, each of which must be legal
+pub fn play(lm: &mut LogicManager, moves: &str) {
+    for san in moves.split_whitespace() {
+        let mv = lm.parse_san(san).unwrap();
+        lm.make_move(mv).unwrap();
+    }
+}
+
+//the position of fen played by the rules of the variant V
+pub fn variant_game<V: Variant + Default + 'static>(fen: &str) -> LogicManager {
+    LogicManager::from_fen_with_variant(fen, Box::new(V::default())).unwrap()
+}
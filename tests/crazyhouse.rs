@@ -0,0 +1,151 @@
+use chess::logic::{LogicManager, GameStatus, Move, Square, Color, FenError};
+use chess::logic::variant::Crazyhouse;
+use chess::logic::piece::PieceTypes;
+use chess::logic::pgn::Pgn;
+use std::convert::TryFrom;
+
+mod common;
+use common::{play, variant_game};
+
+fn held(lm: &LogicManager, color: Color, piece_type: PieceTypes) -> u8 {
+    lm.variant().as_any().downcast_ref::<Crazyhouse>().unwrap().held(color, piece_type)
+}
+
+#[test]
+fn pockets_in_fen() {
+    let lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    assert_eq!(lm.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1");
+    assert_eq!(lm.variant().pocket(Color::White).unwrap().len(), 5);
+    assert_eq!(LogicManager::new().variant().pocket(Color::White), None);
+
+    let lm = variant_game::<Crazyhouse>("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R[QNpp] b KQkq - 3 3");
+    assert_eq!(held(&lm, Color::White, PieceTypes::Queen), 1);
+    assert_eq!(held(&lm, Color::White, PieceTypes::Knight), 1);
+    assert_eq!(held(&lm, Color::Black, PieceTypes::Pawn), 2);
+    assert_eq!(lm.to_fen(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R[QNpp] b KQkq - 3 3");
+    //the pockets may also be written as a ninth rank
+    let lm = variant_game::<Crazyhouse>("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R/ppNQ b KQkq - 3 3");
+    assert_eq!(lm.to_fen(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R[QNpp] b KQkq - 3 3");
+    //promoted pieces are marked with a tilde
+    let lm = variant_game::<Crazyhouse>("Q~3k3/8/8/8/8/8/8/4K2q~[Rp] w - - 0 1");
+    assert_eq!(lm.to_fen(), "Q~3k3/8/8/8/8/8/8/4K2q~[Rp] w - - 0 1");
+    let crazyhouse = lm.variant().as_any().downcast_ref::<Crazyhouse>().unwrap();
+    assert!(crazyhouse.is_promoted((0, 0)) && crazyhouse.is_promoted((7, 7)) && !crazyhouse.is_promoted((0, 4)));
+
+    for fen in ["8/8/8/8/8/8/8/K6k[K] w - - 0 1", "8/8/8/8/8/8/8/K6k[Q w - - 0 1", "~8/8/8/8/8/8/8/K6k[] w - - 0 1"] {
+        assert!(matches!(LogicManager::from_fen_with_variant(fen, Box::new(Crazyhouse::default())), Err(FenError::Variant(_))), "{}", fen);
+    }
+    assert!(LogicManager::from_fen("8/8/8/8/8/8/8/K6k[] w - - 0 1").is_err());
+}
+
+#[test]
+fn captured_pieces_go_into_the_pocket() {
+    let mut lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    play(&mut lm, "e4 d5 exd5 Qxd5 Nc3 Qxg2 Bxg2");
+    assert_eq!(lm.to_fen(), "rnb1kbnr/ppp1pppp/8/8/8/2N5/PPPP1PBP/R1BQK1NR[QPpp] b KQkq - 0 4");
+    assert_eq!(held(&lm, Color::White, PieceTypes::Queen), 1);
+    assert_eq!(held(&lm, Color::Black, PieceTypes::Pawn), 2);
+    assert!(lm.undo());
+    assert_eq!(held(&lm, Color::White, PieceTypes::Queen), 0);
+    assert!(lm.redo());
+    assert_eq!(held(&lm, Color::White, PieceTypes::Queen), 1);
+}
+
+#[test]
+fn promoted_pieces_are_taken_as_pawns() {
+    let mut lm = variant_game::<Crazyhouse>("r3k3/1P6/8/8/8/5b2/8/4K3[] w - - 0 1");
+    play(&mut lm, "bxa8=Q+");
+    assert_eq!(lm.to_fen(), "Q~3k3/8/8/8/8/5b2/8/4K3[R] b - - 0 1");
+    play(&mut lm, "Bxa8");
+    assert_eq!(lm.to_fen(), "b3k3/8/8/8/8/8/8/4K3[Rp] w - - 0 2");
+}
+
+#[test]
+fn drops() {
+    let mut lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    play(&mut lm, "e4 d5 exd5 Nf6");
+    assert!(lm.drop_squares(PieceTypes::Knight).is_empty());
+    //pawns can not be dropped on the first or last rank
+    let squares = lm.drop_squares(PieceTypes::Pawn);
+    assert_eq!(squares.len(), 32);
+    assert!(squares.iter().all(|pos| pos.0 != 0 && pos.0 != 7 && lm.get_piece_color(*pos).is_none()));
+    assert_eq!(lm.legal_moves().len(), 32 + LogicManager::from_fen("rnbqkb1r/ppp1pppp/5n2/3P4/8/8/PPPP1PPP/RNBQKBNR w KQkq - 1 3").unwrap().legal_moves().len());
+
+    let mv = lm.parse_san("P@e6").unwrap();
+    assert_eq!(mv, Move::piece_drop(PieceTypes::Pawn, Square::new(2, 4)));
+    assert_eq!(lm.parse_san("@e6"), Ok(mv));
+    assert_eq!(mv.to_uci(), "P@e6");
+    assert!(lm.parse_san("N@e6").is_err());
+    assert!(lm.parse_san("P@e8").is_err());
+    assert!(lm.parse_san("P@d5").is_err());
+    let outcome = lm.make_move(mv).unwrap();
+    assert_eq!(outcome.san, "P@e6");
+    assert_eq!(outcome.moved, PieceTypes::Pawn);
+    assert_eq!(lm.to_fen(), "rnbqkb1r/ppp1pppp/4Pn2/3P4/8/8/PPPP1PPP/RNBQKBNR[] b KQkq - 0 3");
+    assert!(lm.undo());
+    assert_eq!(held(&lm, Color::White, PieceTypes::Pawn), 1);
+
+    //a pawn dropped on its starting rank may still move two squares
+    let mut lm = variant_game::<Crazyhouse>("4k3/8/8/8/8/8/8/4K3[Pp] w - - 0 1");
+    play(&mut lm, "P@a2 P@h7 a4");
+    assert_eq!(lm.to_fen(), "4k3/7p/8/8/P7/8/8/4K3[] b - a3 0 2");
+}
+
+#[test]
+fn drops_must_block_check() {
+    let lm = variant_game::<Crazyhouse>("4r1k1/8/8/8/8/8/8/4K3[N] w - - 0 1");
+    let mut squares = lm.drop_squares(PieceTypes::Knight);
+    squares.sort_unstable();
+    assert_eq!(squares, vec![(1, 4), (2, 4), (3, 4), (4, 4), (5, 4), (6, 4)]);
+}
+
+#[test]
+fn checkmate_by_a_drop() {
+    let mut lm = variant_game::<Crazyhouse>("6rk/6pp/8/8/8/8/8/K7[N] w - - 0 1");
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    play(&mut lm, "N@f7");
+    assert_eq!(lm.get_san_moves().last().unwrap(), "N@f7#");
+    assert_eq!(lm.status(), GameStatus::Checkmate(Color::White));
+
+    //a piece in hand can still be dropped to mate, and a drop can parry a mate
+    assert_eq!(variant_game::<Crazyhouse>("K7/8/8/8/8/8/8/7k[N] w - - 0 1").status(), GameStatus::Ongoing);
+    assert_eq!(variant_game::<Crazyhouse>("K7/8/8/8/8/8/8/7k[] w - - 0 1").status(), GameStatus::InsufficientMaterial);
+    assert_eq!(variant_game::<Crazyhouse>("k7/8/1K6/8/8/8/8/7R[] b - - 0 1").status(), GameStatus::Ongoing);
+    assert_eq!(variant_game::<Crazyhouse>("k6R/8/1K6/8/8/8/8/8[] b - - 0 1").status(), GameStatus::Checkmate(Color::White));
+    assert_eq!(variant_game::<Crazyhouse>("k6R/8/1K6/8/8/8/8/8[b] b - - 0 1").status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn repetitions_include_the_pockets() {
+    let mut lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    play(&mut lm, "e4 d5 exd5 Qxd5");
+    let hash = lm.position_hash();
+    assert_eq!(hash, variant_game::<Crazyhouse>(&lm.to_fen()).position_hash());
+    assert_ne!(hash, variant_game::<Crazyhouse>("rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[] w KQkq - 0 3").position_hash());
+    play(&mut lm, "Nf3 Qd8 Ng1 Qd5 Nf3 Qd8 Ng1 Qd5");
+    assert_eq!(lm.repetitions(), 3);
+    play(&mut lm, "P@e4");
+    assert_eq!(lm.repetitions(), 1);
+}
+
+#[test]
+fn saved_games_keep_the_drops() {
+    let mut lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    play(&mut lm, "e4 d5 exd5 Qxd5 Nc3 Qa5 P@d5");
+    let pgn = Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("Variant"), Some("Crazyhouse"));
+    assert_eq!(pgn.get_tag("FEN"), None);
+    assert!(pgn.to_string().contains("4. P@d5"));
+    let replayed = Pgn::parse(&pgn.to_string()).unwrap().replay().unwrap();
+    assert_eq!(replayed.to_fen(), lm.to_fen());
+    assert_eq!(replayed.get_moves(), lm.get_moves());
+
+    let loaded = LogicManager::try_from(lm.to_record()).unwrap();
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+    assert_eq!(loaded.variant().name(), "Crazyhouse");
+
+    let lm = variant_game::<Crazyhouse>("4k3/8/8/8/8/8/8/4K3[Qq] w - - 0 1");
+    let pgn = Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("FEN"), Some("4k3/8/8/8/8/8/8/4K3[Qq] w - - 0 1"));
+    assert_eq!(pgn.replay().unwrap().to_fen(), lm.to_fen());
+}
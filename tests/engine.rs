@@ -0,0 +1,29 @@
+use chess::engine::{Engine, SearchLimits};
+use chess::logic::LogicManager;
+
+fn best_uci(fen: &str, depth: u32) -> String {
+    let lm = LogicManager::from_fen(fen).unwrap();
+    Engine::best_move(&lm, SearchLimits::depth(depth)).unwrap().to_uci()
+}
+
+#[test]
+fn finds_mate_in_one() {
+    assert_eq!(best_uci("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2), "a1a8");
+    assert_eq!(best_uci("r5k1/8/8/8/8/8/5PPP/6K1 b - - 0 1", 2), "a8a1");
+}
+
+#[test]
+fn takes_hanging_queen() {
+    assert_eq!(best_uci("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2), "d2d5");
+}
+
+#[test]
+fn does_not_take_defended_pawn_with_queen() {
+    assert_ne!(best_uci("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 3), "d1d5");
+}
+
+#[test]
+fn no_move_when_game_is_over() {
+    let lm = LogicManager::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1").unwrap();
+    assert_eq!(Engine::best_move(&lm, SearchLimits::depth(2)), None);
+}
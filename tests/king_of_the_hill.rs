@@ -0,0 +1,38 @@
+use chess::logic::{LogicManager, GameStatus, Color};
+use chess::logic::variant::{KingOfTheHill, variant_from_name};
+use chess::logic::pgn::Pgn;
+
+mod common;
+use common::{play, variant_game};
+
+#[test]
+fn reaching_the_hill_wins() {
+    let mut lm = LogicManager::with_variant(variant_from_name("King of the Hill").unwrap());
+    assert_eq!(lm.to_fen(), chess::logic::STARTING_FEN);
+    let mut hill = lm.variant().marked_squares(&lm);
+    hill.sort_unstable();
+    assert_eq!(hill, vec![(3, 3), (3, 4), (4, 3), (4, 4)]);
+    play(&mut lm, "e4 e5 Ke2 Ke7 Kd3 Kf6 Kc4");
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    play(&mut lm, "Kg6 Kd5");
+    assert_eq!(lm.status(), GameStatus::VariantWin(Color::White));
+    assert_eq!(lm.variant().win_reason(), "by reaching the hill");
+    assert_eq!(Pgn::from_game(&lm).result, "1-0");
+    let pgn = Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("Variant"), Some("King of the Hill"));
+    assert_eq!(Pgn::parse(&pgn.to_string()).unwrap().replay().unwrap().status(), GameStatus::VariantWin(Color::White));
+}
+
+#[test]
+fn the_hill_can_not_be_reached_through_check() {
+    let lm = variant_game::<KingOfTheHill>("4k3/8/8/3r4/8/4K3/8/8 w - - 0 1");
+    assert!(lm.parse_san("Kd4").is_err());
+    assert!(lm.parse_san("Ke4").is_ok());
+}
+
+#[test]
+fn bare_kings_play_on() {
+    assert_eq!(variant_game::<KingOfTheHill>("4k3/8/8/8/8/8/8/4K3 w - - 0 1").status(), GameStatus::Ongoing);
+    assert_eq!(variant_game::<KingOfTheHill>("8/8/8/4k3/8/8/8/4K3 w - - 0 1").status(), GameStatus::VariantWin(Color::Black));
+    assert_eq!(LogicManager::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().status(), GameStatus::InsufficientMaterial);
+}
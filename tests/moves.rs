@@ -0,0 +1,152 @@
+use chess::logic::{LogicManager, Move, MoveKind, Square, IllegalMove, Color};
+use chess::logic::piece::PieceTypes;
+
+fn sqr(name: &str) -> Square {
+    Square::from_name(name).unwrap()
+}
+
+#[test]
+fn squares() {
+    assert_eq!(sqr("e1"), Square::new(7, 4));
+    assert_eq!(sqr("a8").pos(), (0, 0));
+    assert_eq!(Square::from((4, 3)).to_string(), "d4");
+    assert_eq!(Square::from_name("i1"), None);
+    assert_eq!(Square::from_name("e9"), None);
+}
+
+#[test]
+fn move_kinds() {
+    let lm = LogicManager::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+    let kind = |san: &str| lm.parse_san(san).unwrap().kind;
+    assert_eq!(kind("Kf1"), MoveKind::Normal);
+    assert_eq!(kind("Rxa8+"), MoveKind::Capture);
+    assert_eq!(kind("exd6"), MoveKind::EnPassant);
+    assert_eq!(kind("O-O"), MoveKind::Castle);
+    assert_eq!(kind("b8=Q"), MoveKind::Promotion);
+    assert_eq!(kind("bxa8=N"), MoveKind::Promotion);
+}
+
+#[test]
+fn outcome_of_capture() {
+    let mut lm = LogicManager::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    lm.get_possible_moves((4, 4));
+    let outcome = lm.move_piece((3, 3)).unwrap();
+    assert_eq!(outcome.mv.kind, MoveKind::Capture);
+    assert_eq!(outcome.moved, PieceTypes::Pawn);
+    assert_eq!(outcome.captured, Some((sqr("d5"), PieceTypes::Pawn)));
+    assert_eq!(outcome.rook_move, None);
+    assert_eq!(outcome.san, "exd5");
+    assert!(!outcome.check);
+}
+
+#[test]
+fn outcome_of_en_passant() {
+    let mut lm = LogicManager::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    lm.get_possible_moves((3, 4));
+    let outcome = lm.move_piece((2, 3)).unwrap();
+    assert_eq!(outcome.mv.kind, MoveKind::EnPassant);
+    assert_eq!(outcome.captured, Some((sqr("d5"), PieceTypes::Pawn)));
+    assert_eq!(outcome.san, "exd6");
+}
+
+#[test]
+fn outcome_of_castling() {
+    let mut lm = LogicManager::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+    lm.get_possible_moves((0, 4));
+    let outcome = lm.move_piece((0, 2)).unwrap();
+    assert_eq!(outcome.mv.kind, MoveKind::Castle);
+    assert_eq!(outcome.moved, PieceTypes::King);
+    assert_eq!(outcome.captured, None);
+    assert_eq!(outcome.rook_move, Some((sqr("a8"), sqr("d8"))));
+    assert_eq!(outcome.san, "O-O-O");
+}
+
+#[test]
+fn outcome_of_promotion() {
+    let mut lm = LogicManager::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    lm.get_possible_moves((1, 0));
+    assert_eq!(lm.move_piece((0, 1)), None);
+    let outcome = lm.promote_pawn((0, 1), PieceTypes::Queen).unwrap();
+    assert_eq!(outcome.mv.kind, MoveKind::Promotion);
+    assert_eq!(outcome.mv.promotion, Some(PieceTypes::Queen));
+    assert_eq!(outcome.moved, PieceTypes::Pawn);
+    assert_eq!(outcome.captured, Some((sqr("b8"), PieceTypes::Rook)));
+    assert_eq!(outcome.san, "axb8=Q+");
+    assert!(outcome.check);
+}
+
+#[test]
+fn legal_moves() {
+    let lm = LogicManager::new();
+    let moves = lm.legal_moves();
+    assert_eq!(moves.len(), 20);
+    assert!(moves.contains(&Move::new(sqr("g1"), sqr("f3"), None, MoveKind::Normal)));
+    //one move for each piece a pawn can promote into
+    let lm = LogicManager::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(lm.legal_moves().iter().filter(|mv| mv.kind == MoveKind::Promotion).count(), 4);
+}
+
+#[test]
+fn make_move_without_selection() {
+    let mut lm = LogicManager::new();
+    //a selection made by a front-end does not get in the way
+    lm.get_possible_moves((6, 0));
+    let outcome = lm.make_move(Move::new(sqr("e2"), sqr("e4"), None, MoveKind::Normal)).unwrap();
+    assert_eq!(outcome.san, "e4");
+    assert!(!lm.can_move());
+    let outcome = lm.make_move(lm.parse_san("d5").unwrap()).unwrap();
+    assert_eq!(outcome.mv.from, sqr("d7"));
+    let outcome = lm.make_move(Move::new(sqr("e4"), sqr("d5"), None, MoveKind::Capture)).unwrap();
+    assert_eq!(outcome.captured, Some((sqr("d5"), PieceTypes::Pawn)));
+    assert_eq!(lm.to_fen(), "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2");
+}
+
+#[test]
+fn make_move_rejects_illegal_moves() {
+    let mut lm = LogicManager::from_fen("4k3/P7/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+    let fen = lm.to_fen();
+    let illegal = [
+        Move::new(sqr("e1"), sqr("f1"), None, MoveKind::Normal), //does not escape check
+        Move::new(sqr("e8"), sqr("e7"), None, MoveKind::Normal), //not the side to move
+        Move::new(sqr("d4"), sqr("d5"), None, MoveKind::Normal), //no piece there
+        Move::new(sqr("a7"), sqr("a8"), None, MoveKind::Normal), //promotion without a piece
+        Move::new(sqr("a7"), sqr("a8"), Some(PieceTypes::King), MoveKind::Promotion),
+        Move::new(sqr("e1"), sqr("d2"), None, MoveKind::Capture), //wrong kind
+    ];
+    for mv in illegal.iter() {
+        assert_eq!(lm.make_move(*mv), Err(IllegalMove(*mv)));
+        assert_eq!(lm.to_fen(), fen);
+    }
+    assert!(lm.make_move(Move::new(sqr("e1"), sqr("d2"), None, MoveKind::Normal)).is_ok());
+}
+
+#[test]
+fn refuses_the_wrong_color() {
+    let mut lm = LogicManager::new();
+    assert_eq!(lm.side_to_move(), Color::White);
+    assert_eq!(lm.get_possible_moves((1, 4)), None);
+    assert!(!lm.can_move());
+    assert_eq!(lm.move_piece((3, 4)), None);
+    assert_eq!(lm.to_fen(), chess::logic::STARTING_FEN);
+
+    lm.get_possible_moves((6, 4));
+    assert!(lm.move_piece((4, 4)).is_some());
+    assert_eq!(lm.side_to_move(), Color::Black);
+    assert_eq!(lm.get_possible_moves((6, 3)), None);
+    assert!(lm.get_possible_moves((1, 4)).is_some());
+    //a square the selected piece cannot reach is refused too
+    assert_eq!(lm.move_piece((2, 3)), None);
+    assert_eq!(lm.side_to_move(), Color::Black);
+}
+
+#[test]
+fn notation_of_a_stopped_game() {
+    let mut lm = LogicManager::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+    let check = lm.parse_san("Ra8").unwrap();
+    lm.stop();
+    assert_eq!(check.to_san(&lm), "Ra8+");
+    let mut lm = LogicManager::from_fen("6k1/5ppp/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+    let mate = lm.parse_san("Ra8").unwrap();
+    lm.stop();
+    assert_eq!(mate.to_san(&lm), "Ra8#");
+}
@@ -0,0 +1,54 @@
+use chess::logic::LogicManager;
+
+//node counts from https://www.chessprogramming.org/Perft_Results
+fn check_perft(fen: &str, expected: &[u64]) {
+    let mut lm = LogicManager::from_fen(fen).unwrap();
+    for (depth, nodes) in expected.iter().enumerate() {
+        assert_eq!(lm.perft(depth as u32 + 1), *nodes, "perft({}) of {}", depth + 1, fen);
+    }
+    assert_eq!(lm.to_fen(), LogicManager::from_fen(fen).unwrap().to_fen(), "perft changed the position");
+    assert!(!lm.can_undo() && !lm.can_redo(), "perft changed the history");
+}
+
+#[test]
+fn perft_starting_position() {
+    check_perft("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &[20, 400, 8902]);
+}
+
+#[test]
+fn perft_kiwipete() {
+    check_perft("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", &[48, 2039, 97862]);
+}
+
+#[test]
+fn perft_position_3() {
+    check_perft("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn perft_position_4() {
+    check_perft("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_4_mirrored() {
+    check_perft("r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1", &[6, 264, 9467]);
+}
+
+#[test]
+fn perft_position_5() {
+    check_perft("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", &[44, 1486, 62379]);
+}
+
+#[test]
+fn perft_position_6() {
+    check_perft("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", &[46, 2079, 89890]);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let mut lm = LogicManager::new();
+    let divide = lm.divide(3);
+    assert_eq!(divide.len(), 20);
+    assert_eq!(divide.iter().map(|(_mv, nodes)| nodes).sum::<u64>(), lm.perft(3));
+}
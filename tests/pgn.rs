@@ -0,0 +1,140 @@
+use chess::logic::{LogicManager, GameStatus};
+use chess::logic::pgn::{Pgn, PgnMove, PgnError, LoadError};
+
+fn sans(moves: &[PgnMove]) -> Vec<&str> {
+    moves.iter().map(|pgn_move| pgn_move.san.as_str()).collect()
+}
+
+#[test]
+fn headers() {
+    let pgn = Pgn::parse("[Event \"F/S Return Match\"]\n[Site \"Belgrade, Serbia JUG\"]\n[White \"Fischer, Robert J.\"]\n\
+        [Annotator \"A \\\"quoted\\\" name\"]\n[Result \"1/2-1/2\"]\n\n1. e4 1/2-1/2").unwrap();
+    assert_eq!(pgn.get_tag("Event"), Some("F/S Return Match"));
+    assert_eq!(pgn.get_tag("White"), Some("Fischer, Robert J."));
+    assert_eq!(pgn.get_tag("Annotator"), Some("A \"quoted\" name"));
+    assert_eq!(pgn.get_tag("Round"), None);
+    assert_eq!(pgn.result, "1/2-1/2");
+    assert!(matches!(Pgn::parse("[Event \"unterminated]\n1. e4 *"), Err(PgnError::Syntax(_))));
+    assert!(matches!(Pgn::parse("[Event]\n1. e4 *"), Err(PgnError::Syntax(_))));
+}
+
+#[test]
+fn move_numbers() {
+    let pgn = Pgn::parse("1.e4 e5 2.Nf3 2...Nc6 3. Bb5 3... a6 *").unwrap();
+    assert_eq!(sans(&pgn.moves), vec!["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+    //a game starting with black to move
+    let pgn = Pgn::parse("[SetUp \"1\"]\n[FEN \"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1\"]\n\n1...e5 2.Nf3 *").unwrap();
+    assert_eq!(sans(&pgn.moves), vec!["e5", "Nf3"]);
+    assert!(pgn.to_string().contains("1... e5 2. Nf3 *"));
+    assert_eq!(pgn.replay().unwrap().get_san_moves(), &vec!["e5".to_string(), "Nf3".to_string()]);
+}
+
+#[test]
+fn comments() {
+    let pgn = Pgn::parse("{Opening comment} 1. e4 {best by test} e5 ; a rest of line comment\n2. Nf3 *").unwrap();
+    assert_eq!(pgn.comments, vec!["Opening comment"]);
+    assert_eq!(pgn.moves[0].comments, vec!["best by test"]);
+    assert_eq!(pgn.moves[1].comments, vec!["a rest of line comment"]);
+    assert_eq!(sans(&pgn.moves), vec!["e4", "e5", "Nf3"]);
+    assert!(matches!(Pgn::parse("1. e4 {unterminated"), Err(PgnError::Syntax(_))));
+}
+
+#[test]
+fn annotations() {
+    let pgn = Pgn::parse("1. e4! e5?! 2. Nf3 $14 Nc6 $2 $32 3. Bb5!! *").unwrap();
+    assert_eq!(sans(&pgn.moves), vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+    assert_eq!(pgn.moves[0].nags, vec![1]);
+    assert_eq!(pgn.moves[1].nags, vec![6]);
+    assert_eq!(pgn.moves[2].nags, vec![14]);
+    assert_eq!(pgn.moves[3].nags, vec![2, 32]);
+    assert_eq!(pgn.moves[4].nags, vec![3]);
+    assert_eq!(Pgn::parse("1. e4!? *").unwrap().moves[0].nags, vec![5]);
+    assert!(matches!(Pgn::parse("1. e4!?! *"), Err(PgnError::Syntax(_))));
+    assert!(matches!(Pgn::parse("$1 1. e4 *"), Err(PgnError::Syntax(_))));
+}
+
+#[test]
+fn variations() {
+    let pgn = Pgn::parse("1. e4 e5 (1... c5 2. Nf3 (2. c3 d5) 2... d6) ( {Better is} 1... e6 ) 2. Nf3 *").unwrap();
+    assert_eq!(sans(&pgn.moves), vec!["e4", "e5", "Nf3"]);
+    let variations = &pgn.moves[1].variations;
+    assert_eq!(variations.len(), 2);
+    assert_eq!(sans(&variations[0]), vec!["c5", "Nf3", "d6"]);
+    assert_eq!(sans(&variations[0][1].variations[0]), vec!["c3", "d5"]);
+    assert_eq!(variations[1][0].leading_comments, vec!["Better is"]);
+    //variations are written back and read the same
+    assert_eq!(Pgn::parse(&pgn.to_string()).unwrap(), pgn);
+    //only the main line is played
+    assert_eq!(pgn.replay().unwrap().get_moves().len(), 3);
+    assert!(matches!(Pgn::parse("1. e4 (1. d4 *"), Err(PgnError::Syntax(_))));
+}
+
+#[test]
+fn multiple_games() {
+    let text = "[Event \"First\"]\n\n1. e4 e5 1-0\n\n[Event \"Second\"]\n\n1. d4 d5 2. c4 0-1\n\n[Event \"Third\"]\n\n*\n";
+    let games = Pgn::parse_all(text).unwrap();
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[0].get_tag("Event"), Some("First"));
+    assert_eq!(sans(&games[1].moves), vec!["d4", "d5", "c4"]);
+    assert_eq!(games[1].result, "0-1");
+    assert!(games[2].moves.is_empty());
+    //parse reads the first game only
+    assert_eq!(Pgn::parse(text).unwrap(), games[0]);
+    assert!(Pgn::parse_all("").unwrap().is_empty());
+}
+
+#[test]
+fn castling_with_zeros() {
+    let pgn = Pgn::parse("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 d6 5. d4 Bg4 6. Nc3 Qd7 7. Be3 0-0-0 *").unwrap();
+    assert_eq!(pgn.moves[6].san, "0-0");
+    assert_eq!(pgn.moves[13].san, "0-0-0");
+    let lm = pgn.replay().unwrap();
+    assert_eq!(lm.get_san_moves()[6], "O-O");
+    assert_eq!(lm.get_san_moves()[13], "O-O-O");
+    assert_eq!(lm.to_fen(), "2kr2nr/pppq1ppp/2np4/2b1p3/2BPP1b1/2N1BN2/PPP2PPP/R2Q1RK1 w - - 5 8");
+}
+
+#[test]
+fn round_trip() {
+    let mut lm = LogicManager::new();
+    for san in "e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7 Re1 b5 Bb3 d6 c3 O-O h3 Nb8 d4 Nbd7".split_whitespace() {
+        let mv = lm.parse_san(san).unwrap();
+        lm.make_move(mv).unwrap();
+    }
+    let mut pgn = Pgn::from_game(&lm);
+    pgn.set_tag("White", "Player, One");
+    pgn.moves[4].comments.push("the Ruy Lopez".to_string());
+    pgn.moves[4].nags.push(1);
+    let text = pgn.to_string();
+    assert!(text.lines().all(|line| line.len() <= 80));
+    let parsed = Pgn::parse(&text).unwrap();
+    assert_eq!(parsed, pgn);
+    let replayed = parsed.replay().unwrap();
+    assert_eq!(replayed.to_fen(), lm.to_fen());
+    assert_eq!(replayed.get_moves(), lm.get_moves());
+    assert_eq!(replayed.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn drawn_results() {
+    //a draw that could be claimed is claimed again
+    let claimed = Pgn::parse("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8 1/2-1/2").unwrap();
+    assert_eq!(claimed.replay().unwrap().status(), GameStatus::ThreefoldRepetition);
+    //an agreed draw is only kept in the result of the PGN
+    let agreed = Pgn::parse("1. e4 e5 1/2-1/2").unwrap();
+    assert_eq!(agreed.result, "1/2-1/2");
+    assert_eq!(agreed.replay().unwrap().status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn load_errors() {
+    let missing = std::env::temp_dir().join("chess_missing_game.pgn");
+    assert!(matches!(LogicManager::load_pgn(missing.to_str().unwrap()), Err(LoadError::Io(_))));
+
+    let path = std::env::temp_dir().join("chess_illegal_game.pgn");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, "1. e4 e5 2. Ke3 *").unwrap();
+    let loaded = LogicManager::load_pgn(path);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(loaded, Err(LoadError::Pgn(PgnError::IllegalMove { move_number: 2, .. }))));
+}
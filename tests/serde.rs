@@ -0,0 +1,119 @@
+#![cfg(feature = "serde")]
+
+use chess::logic::{LogicManager, GameStatus, GameRecord, Move, MoveKind, Square, Color};
+use chess::logic::piece::PieceTypes;
+use chess::logic::variant::Crazyhouse;
+
+mod common;
+use common::play;
+
+fn round_trip(lm: &LogicManager) -> LogicManager {
+    let json = serde_json::to_string(lm).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn moves_and_results() {
+    let lm = LogicManager::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    for mv in lm.legal_moves() {
+        let json = serde_json::to_string(&mv).unwrap();
+        assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), mv);
+    }
+    let mv = Move::new(Square::new(1, 1), Square::new(0, 1), Some(PieceTypes::Queen), MoveKind::Promotion);
+    assert_eq!(serde_json::to_string(&mv).unwrap(),
+               r#"{"from":{"row":1,"col":1},"to":{"row":0,"col":1},"promotion":"Queen","kind":"Promotion"}"#);
+
+    for status in [GameStatus::Ongoing, GameStatus::Checkmate(Color::Black), GameStatus::ThreefoldRepetition] {
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(serde_json::from_str::<GameStatus>(&json).unwrap(), status);
+    }
+
+    let mut lm = LogicManager::from_fen("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1").unwrap();
+    let outcome = lm.make_move(lm.parse_san("O-O").unwrap()).unwrap();
+    let json = serde_json::to_string(&outcome).unwrap();
+    assert_eq!(serde_json::from_str::<chess::logic::MoveOutcome>(&json).unwrap(), outcome);
+}
+
+#[test]
+fn game_in_progress() {
+    let mut lm = LogicManager::new();
+    play(&mut lm, "e4 d5 exd5 Qxd5 Nc3 Qa5 d4 Nf6 Nf3 Bf5 Bc4 e6 O-O");
+    let loaded = round_trip(&lm);
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+    assert_eq!(loaded.get_san_moves(), lm.get_san_moves());
+    assert_eq!(loaded.position_hash(), lm.position_hash());
+    assert_eq!(loaded.side_to_move(), Color::Black);
+    assert_eq!(loaded.legal_moves(), lm.legal_moves());
+
+    //the history survives, moves can be taken back after loading
+    let mut loaded = loaded;
+    while lm.undo() {
+        assert!(loaded.undo());
+        assert_eq!(loaded.to_fen(), lm.to_fen());
+    }
+    assert!(!loaded.undo());
+}
+
+#[test]
+fn undone_moves_can_be_redone() {
+    let mut lm = LogicManager::new();
+    play(&mut lm, "d4 d5 c4 e6");
+    lm.undo();
+    lm.undo();
+    let mut loaded = round_trip(&lm);
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+    assert_eq!(loaded.get_undone_moves(), lm.get_undone_moves());
+    assert!(loaded.redo());
+    assert!(loaded.redo());
+    assert!(!loaded.redo());
+    assert_eq!(loaded.get_san_moves().join(" "), "d4 d5 c4 e6");
+}
+
+#[test]
+fn custom_start_and_claimed_draw() {
+    let mut lm = LogicManager::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    play(&mut lm, "Kf1 Kd8 Ke1 Ke8 Kf1 Kd8 Ke1 Ke8 Kf1 Kd8 Ke1 Ke8");
+    assert_eq!(lm.claim_draw(), Some(GameStatus::ThreefoldRepetition));
+    let loaded = round_trip(&lm);
+    assert_eq!(loaded.get_start_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+    assert_eq!(loaded.status(), GameStatus::ThreefoldRepetition);
+    assert_eq!(loaded.repetitions(), lm.repetitions());
+}
+
+#[test]
+fn invalid_records_are_rejected() {
+    let mut record = LogicManager::new().to_record();
+    record.moves.push(Move::new(Square::new(6, 4), Square::new(3, 4), None, MoveKind::Normal));
+    let json = serde_json::to_string(&record).unwrap();
+    assert!(serde_json::from_str::<LogicManager>(&json).is_err());
+
+    let mut record = LogicManager::new().to_record();
+    record.claimed_draw = Some(GameStatus::FiftyMoveRule);
+    assert!(serde_json::from_str::<LogicManager>(&serde_json::to_string(&record).unwrap()).is_err());
+
+    let record = GameRecord { start_fen: "not a fen".to_string(), moves: vec![], undone: vec![], claimed_draw: None, chess960: false, variant: "Standard".to_string() };
+    assert!(serde_json::from_str::<LogicManager>(&serde_json::to_string(&record).unwrap()).is_err());
+}
+
+#[test]
+fn chess960_games() {
+    let mut lm = LogicManager::from_fen_chess960("rk4r1/pppppppp/8/8/8/8/PPPPPPPP/RK4R1 w GAga - 0 1").unwrap();
+    play(&mut lm, "O-O a5");
+    let loaded = round_trip(&lm);
+    assert!(loaded.is_chess960());
+    assert_eq!(loaded.to_shredder_fen(), lm.to_shredder_fen());
+    //records written before Chess960 support are standard games
+    let json = r#"{"start_fen":"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1","moves":[],"undone":[],"claimed_draw":null}"#;
+    assert!(!serde_json::from_str::<LogicManager>(json).unwrap().is_chess960());
+}
+
+#[test]
+fn crazyhouse_games() {
+    let mut lm = LogicManager::with_variant(Box::new(Crazyhouse::default()));
+    play(&mut lm, "e4 d5 exd5 Qxd5 Nc3 Qa5 P@d5");
+    let json = serde_json::to_string(&lm.get_moves()[6]).unwrap();
+    assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), Move::piece_drop(PieceTypes::Pawn, Square::new(3, 3)));
+    let loaded = round_trip(&lm);
+    assert_eq!(loaded.variant().name(), "Crazyhouse");
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+}
@@ -0,0 +1,142 @@
+use chess::logic::{LogicManager, GameStatus, Color};
+use chess::logic::pgn::Pgn;
+
+mod common;
+use common::play;
+
+fn status(fen: &str) -> GameStatus {
+    LogicManager::from_fen(fen).unwrap().status()
+}
+
+#[test]
+fn insufficient_material() {
+    //king against king
+    assert_eq!(status("8/8/4k3/8/8/3K4/8/8 w - - 0 1"), GameStatus::InsufficientMaterial);
+    //king and bishop against king
+    assert_eq!(status("8/8/4k3/8/8/3K4/5B2/8 w - - 0 1"), GameStatus::InsufficientMaterial);
+    //king and knight against king
+    assert_eq!(status("8/8/4k3/8/8/3K4/5n2/8 b - - 0 1"), GameStatus::InsufficientMaterial);
+    //bishops of both sides on light squares
+    assert_eq!(status("3k4/8/4b3/8/8/3K4/8/7B w - - 0 1"), GameStatus::InsufficientMaterial);
+    //several bishops, all on light squares
+    assert_eq!(status("8/8/4k3/1b6/8/3K4/6B1/7B w - - 0 1"), GameStatus::InsufficientMaterial);
+    //several bishops, all on dark squares
+    assert_eq!(status("8/8/4k3/2b5/8/3K4/5B2/6B1 w - - 0 1"), GameStatus::InsufficientMaterial);
+}
+
+#[test]
+fn sufficient_material() {
+    //bishops on squares of different colors
+    assert_eq!(status("8/8/4k3/4b3/8/3K4/8/7B w - - 0 1"), GameStatus::Ongoing);
+    //two knights can still mate if the defender helps
+    assert_eq!(status("8/8/4k3/8/8/3K4/4NN2/8 w - - 0 1"), GameStatus::Ongoing);
+    //knight against knight
+    assert_eq!(status("8/8/4k3/3n4/8/3K4/5N2/8 w - - 0 1"), GameStatus::Ongoing);
+    //bishop against knight
+    assert_eq!(status("8/8/4k3/3n4/8/3K4/5B2/8 w - - 0 1"), GameStatus::Ongoing);
+    //a single pawn
+    assert_eq!(status("8/8/4k3/8/8/3K4/4P3/8 w - - 0 1"), GameStatus::Ongoing);
+}
+
+#[test]
+fn checkmate_and_stalemate() {
+    assert_eq!(status("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1"), GameStatus::Checkmate(Color::White));
+    assert_eq!(status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), GameStatus::Stalemate);
+}
+
+#[test]
+fn check_takes_the_color_of_the_king() {
+    let lm = LogicManager::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1").unwrap();
+    assert!(lm.is_check(Color::Black));
+    assert!(lm.is_checkmate(Color::Black));
+    assert!(!lm.is_check(Color::White));
+    assert!(!lm.is_checkmate(Color::White));
+    //check without mate
+    let lm = LogicManager::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    assert!(!lm.is_check(Color::Black));
+    let lm = LogicManager::from_fen("6k1/5pp1/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    assert!(!lm.is_check(Color::White));
+    let lm = LogicManager::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+    assert!(lm.is_check(Color::White));
+    assert!(!lm.is_checkmate(Color::White));
+    assert_eq!(Color::White.opposite(), Color::Black);
+    assert_eq!(Color::from(false), Color::Black);
+}
+
+#[test]
+fn threefold_repetition_is_claimable() {
+    let mut lm = LogicManager::new();
+    play(&mut lm, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1");
+    assert_eq!(lm.claimable_draw(), None);
+    play(&mut lm, "Ng8");
+    assert_eq!(lm.repetitions(), 3);
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    assert_eq!(lm.claimable_draw(), Some(GameStatus::ThreefoldRepetition));
+    assert_eq!(lm.claim_draw(), Some(GameStatus::ThreefoldRepetition));
+    assert_eq!(lm.status(), GameStatus::ThreefoldRepetition);
+    //taking back the move also takes back the claim
+    lm.undo();
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn saved_games_keep_the_claimed_draw() {
+    let path = std::env::temp_dir().join("chess_claimed_draw.pgn");
+    let path = path.to_str().unwrap();
+    let mut lm = LogicManager::new();
+    play(&mut lm, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8");
+    lm.claim_draw();
+    Pgn::from_game(&lm).save(path).unwrap();
+    let loaded = LogicManager::load_pgn(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded.status(), GameStatus::ThreefoldRepetition);
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+    assert_eq!(loaded.get_moves(), lm.get_moves());
+}
+
+#[test]
+fn fivefold_repetition_is_automatic() {
+    let mut lm = LogicManager::new();
+    play(&mut lm, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1");
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    play(&mut lm, "Ng8");
+    assert_eq!(lm.status(), GameStatus::FivefoldRepetition);
+}
+
+#[test]
+fn repetition_needs_same_castling_rights() {
+    let mut lm = LogicManager::new();
+    play(&mut lm, "Nf3 Nf6 Rg1 Rg8 Rh1 Rh8 Ng1 Ng8");
+    //the pieces are back where they started, but neither side may castle kingside anymore
+    assert_eq!(lm.repetitions(), 1);
+}
+
+#[test]
+fn claim_without_draw_fails() {
+    let mut lm = LogicManager::new();
+    assert_eq!(lm.claim_draw(), None);
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+}
+
+#[test]
+fn halfmove_clock() {
+    let mut lm = LogicManager::from_fen("4k3/4p3/8/8/8/8/4P3/4K1N1 w - - 98 60").unwrap();
+    play(&mut lm, "Nf3");
+    assert_eq!(lm.claimable_draw(), None);
+    play(&mut lm, "Kd8");
+    assert_eq!(lm.to_fen(), "3k4/4p3/8/8/8/5N2/4P3/4K3 w - - 100 61");
+    assert_eq!(lm.claimable_draw(), Some(GameStatus::FiftyMoveRule));
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    //a pawn move resets the clock
+    play(&mut lm, "e4");
+    assert_eq!(lm.to_fen(), "3k4/4p3/8/8/4P3/5N2/8/4K3 b - e3 0 61");
+    assert_eq!(lm.claimable_draw(), None);
+}
+
+#[test]
+fn seventy_five_move_rule_is_automatic() {
+    let mut lm = LogicManager::from_fen("4k3/4p3/8/8/8/8/4P3/4K1N1 w - - 149 90").unwrap();
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    play(&mut lm, "Nf3");
+    assert_eq!(lm.status(), GameStatus::SeventyFiveMoveRule);
+}
@@ -0,0 +1,70 @@
+use chess::logic::{LogicManager, GameStatus, Color, FenError};
+use chess::logic::variant::{ThreeCheck, variant_from_name};
+use chess::logic::pgn::Pgn;
+use std::convert::TryFrom;
+
+mod common;
+use common::{play, variant_game};
+
+fn checks(lm: &LogicManager, color: Color) -> u8 {
+    lm.variant().as_any().downcast_ref::<ThreeCheck>().unwrap().checks(color)
+}
+
+#[test]
+fn checks_in_fen() {
+    let lm = LogicManager::with_variant(Box::new(ThreeCheck::default()));
+    assert_eq!(lm.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +0+0");
+    assert_eq!(lm.variant().state_text(), Some("Checks\nWhite: 0\nBlack: 0".to_string()));
+
+    let lm = variant_game::<ThreeCheck>("4k3/8/8/8/8/8/8/4K2R w K - 4 20 +2+1");
+    assert_eq!((checks(&lm, Color::White), checks(&lm, Color::Black)), (2, 1));
+    assert_eq!(lm.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 4 20 +2+1");
+    //the remaining checks may follow the en passant square instead
+    assert_eq!(variant_game::<ThreeCheck>("4k3/8/8/8/8/8/8/4K2R w K - 1+2 4 20").to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 4 20 +2+1");
+    //positions without checks given start from none
+    assert_eq!(variant_game::<ThreeCheck>("4k3/8/8/8/8/8/8/4K2R w K - 4 20").to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 4 20 +0+0");
+
+    for fen in ["4k3/8/8/8/8/8/8/4K2R w K - 4 20 +2", "4k3/8/8/8/8/8/8/4K2R w K - 4+1 4 20", "4k3/8/8/8/8/8/8/4K2R w K - 4 20 +5+0"] {
+        assert!(matches!(LogicManager::from_fen_with_variant(fen, Box::new(ThreeCheck::default())), Err(FenError::Variant(_))), "{}", fen);
+    }
+}
+
+#[test]
+fn third_check_wins() {
+    let mut lm = LogicManager::with_variant(Box::new(ThreeCheck::default()));
+    play(&mut lm, "e4 e5 Bc4 Nc6 Bxf7+");
+    assert_eq!(checks(&lm, Color::White), 1);
+    assert_eq!(lm.variant().marked_squares(&lm), vec![(0, 4)]);
+    play(&mut lm, "Kxf7 Qh5+ g6 Qxg6+");
+    assert_eq!(checks(&lm, Color::White), 3);
+    assert_eq!(lm.status(), GameStatus::VariantWin(Color::White));
+    assert_eq!(lm.variant().win_reason(), "by three checks");
+    assert_eq!(Pgn::from_game(&lm).result, "1-0");
+    lm.undo();
+    assert_eq!(checks(&lm, Color::White), 2);
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    assert!(lm.variant().marked_squares(&lm).is_empty());
+
+    //checks given change the position for repetitions
+    assert_ne!(lm.position_hash(), variant_game::<ThreeCheck>(&lm.to_fen().replace("+2+0", "+1+0")).position_hash());
+    assert_eq!(lm.position_hash(), variant_game::<ThreeCheck>(&lm.to_fen()).position_hash());
+}
+
+#[test]
+fn minor_pieces_can_still_win() {
+    assert_eq!(variant_game::<ThreeCheck>("4k3/8/8/8/8/8/8/4KN2 w - - 0 1 +0+0").status(), GameStatus::Ongoing);
+    assert_eq!(variant_game::<ThreeCheck>("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +2+2").status(), GameStatus::InsufficientMaterial);
+}
+
+#[test]
+fn saved_games_keep_the_checks() {
+    let mut lm = LogicManager::with_variant(variant_from_name("Three-check").unwrap());
+    play(&mut lm, "e4 e5 Bc4 Nc6 Bxf7+ Kxf7");
+    let pgn = Pgn::from_game(&lm);
+    assert_eq!(pgn.get_tag("Variant"), Some("Three-check"));
+    assert_eq!(pgn.get_tag("FEN"), None);
+    let replayed = Pgn::parse(&pgn.to_string()).unwrap().replay().unwrap();
+    assert_eq!(replayed.to_fen(), lm.to_fen());
+    let loaded = LogicManager::try_from(lm.to_record()).unwrap();
+    assert_eq!(loaded.to_fen(), lm.to_fen());
+}
@@ -0,0 +1,48 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+#[test]
+fn uci_session() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chess"))
+        .arg("--uci")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines().map(|line| line.unwrap());
+
+    writeln!(stdin, "uci").unwrap();
+    assert!(lines.by_ref().any(|line| line == "uciok"));
+    writeln!(stdin, "isready").unwrap();
+    assert_eq!(lines.next().unwrap(), "readyok");
+
+    //white mates with Qxf7
+    writeln!(stdin, "position startpos moves e2e4 e7e5 f1c4 b8c6 d1h5 g8f6").unwrap();
+    writeln!(stdin, "go depth 2").unwrap();
+    let mut info = false;
+    let best = loop {
+        let line = lines.next().unwrap();
+        info |= line.starts_with("info depth");
+        if let Some(best) = line.strip_prefix("bestmove ") {
+            break best.to_string();
+        }
+    };
+    assert!(info);
+    assert_eq!(best, "h5f7");
+
+    writeln!(stdin, "position fen 7k/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+    writeln!(stdin, "go infinite").unwrap();
+    writeln!(stdin, "stop").unwrap();
+    assert!(lines.any(|line| line.starts_with("bestmove ")));
+
+    //with UCI_Chess960 castling is sent as the king moving onto its rook
+    writeln!(stdin, "setoption name UCI_Chess960 value true").unwrap();
+    writeln!(stdin, "position fen 1k6/8/8/8/8/8/8/1K4R1 w G - 0 1 moves b1g1").unwrap();
+    writeln!(stdin, "go depth 1").unwrap();
+    let best = lines.find_map(|line| line.strip_prefix("bestmove ").map(str::to_string)).unwrap();
+    assert!(best.starts_with("b8"), "{}", best);
+
+    writeln!(stdin, "quit").unwrap();
+    assert!(child.wait().unwrap().success());
+}
@@ -0,0 +1,176 @@
+use chess::logic::{LogicManager, GameStatus, Move, Square, Color, STARTING_FEN};
+use chess::logic::variant::{Variant, Standard};
+use chess::logic::piece::PieceTypes;
+use chess::logic::pgn::{Pgn, PgnError};
+use std::any::Any;
+
+mod common;
+use common::play;
+
+//queens may not move
+#[derive(Clone)]
+struct FrozenQueens;
+
+impl Variant for FrozenQueens {
+    fn name(&self) -> &'static str {
+        "Frozen queens"
+    }
+
+    fn filter_moves(&self, lm: &LogicManager, from: (i8, i8), moves: Vec<(i8, i8)>) -> Vec<(i8, i8)> {
+        match lm.get_board()[from.0 as usize][from.1 as usize].as_ref() {
+            Some(piece) if piece.piece_type() == PieceTypes::Queen => vec![],
+            _ => moves,
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+//pawns promote only into knights or kings
+#[derive(Clone)]
+struct OddPromotions;
+
+impl Variant for OddPromotions {
+    fn name(&self) -> &'static str {
+        "Odd promotions"
+    }
+
+    fn promotions(&self) -> Vec<PieceTypes> {
+        vec![PieceTypes::Knight, PieceTypes::King]
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+//a piece that captures is removed along with its victim, and the first side to make two captures wins
+#[derive(Clone, Default)]
+struct Kamikaze {
+    captures: [u8; 2],
+}
+
+impl Variant for Kamikaze {
+    fn name(&self) -> &'static str {
+        "Kamikaze"
+    }
+
+    fn after_move(&mut self, lm: &mut LogicManager, mv: Move, captured: Option<(Square, PieceTypes)>) {
+        if captured.is_some() {
+            lm.remove_piece(mv.to.pos());
+            self.captures[lm.side_to_move() as usize] += 1;
+        }
+    }
+
+    fn status(&self, _lm: &LogicManager) -> Option<GameStatus> {
+        [Color::White, Color::Black].iter().find(|color| self.captures[**color as usize] >= 2).map(|color| GameStatus::VariantWin(*color))
+    }
+
+    fn box_clone(&self) -> Box<dyn Variant> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[test]
+fn standard_is_the_default() {
+    let lm = LogicManager::new();
+    assert_eq!(lm.variant().name(), "Standard");
+    assert!(lm.variant().as_any().downcast_ref::<Standard>().is_some());
+    let lm = LogicManager::with_variant(Box::new(Standard));
+    assert_eq!(lm.to_fen(), STARTING_FEN);
+    assert_eq!(Pgn::from_game(&lm).get_tag("Variant"), None);
+}
+
+#[test]
+fn standard_rules_are_unchanged() {
+    //node counts from https://www.chessprogramming.org/Perft_Results
+    let positions = [
+        (STARTING_FEN, 8902),
+        ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 97862),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 2812),
+        ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 9467),
+        ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", 62379),
+    ];
+    for (fen, nodes) in positions.iter() {
+        let mut lm = LogicManager::from_fen_with_variant(fen, Box::new(Standard)).unwrap();
+        assert_eq!(lm.legal_moves(), LogicManager::from_fen(fen).unwrap().legal_moves(), "{}", fen);
+        assert_eq!(lm.perft(3), *nodes, "{}", fen);
+    }
+    let statuses = [
+        ("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3", GameStatus::Checkmate(Color::Black)),
+        ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", GameStatus::Stalemate),
+        ("8/8/4k3/8/8/3K4/5B2/8 w - - 0 1", GameStatus::InsufficientMaterial),
+        ("4k3/8/8/8/8/8/8/4K2R w K - 150 80", GameStatus::SeventyFiveMoveRule),
+    ];
+    for (fen, status) in statuses.iter() {
+        assert_eq!(LogicManager::from_fen_with_variant(fen, Box::new(Standard)).unwrap().status(), *status, "{}", fen);
+    }
+}
+
+#[test]
+fn filtered_moves() {
+    let mut lm = LogicManager::with_variant(Box::new(FrozenQueens));
+    play(&mut lm, "e4 e5");
+    assert!(lm.parse_san("Qh5").is_err());
+    assert_eq!(lm.get_possible_moves((7, 3)).map(|moves| moves.len()), Some(0));
+    //the queen is the only piece that could move, so it is stalemate
+    let lm = LogicManager::from_fen_with_variant("k7/8/1K6/8/8/8/8/7Q b - - 0 1", Box::new(FrozenQueens)).unwrap();
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    let lm = LogicManager::from_fen_with_variant("k7/P7/1K6/8/8/8/8/7q b - - 0 1", Box::new(FrozenQueens)).unwrap();
+    assert_eq!(lm.status(), GameStatus::Stalemate);
+}
+
+#[test]
+fn promotion_choices() {
+    let mut lm = LogicManager::from_fen_with_variant("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", Box::new(OddPromotions)).unwrap();
+    let promotions = lm.legal_moves().into_iter().filter_map(|mv| mv.promotion).collect::<Vec<_>>();
+    assert_eq!(promotions, vec![PieceTypes::Knight, PieceTypes::King]);
+    assert!(lm.parse_san("a8=Q").is_err());
+    lm.get_possible_moves((1, 0));
+    assert_eq!(lm.promote_pawn((0, 0), PieceTypes::Queen), None);
+    play(&mut lm, "a8=K");
+    assert_eq!(lm.to_fen(), "K3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn side_effects_and_wins() {
+    let mut lm = LogicManager::with_variant(Box::new(Kamikaze::default()));
+    play(&mut lm, "e4 d5 exd5");
+    assert_eq!(lm.to_fen(), "rnbqkbnr/ppp1pppp/8/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2");
+    //pieces removed by the variant leave the same hash as the position set up directly
+    assert_eq!(lm.position_hash(), LogicManager::from_fen(&lm.to_fen()).unwrap().position_hash());
+    play(&mut lm, "Qxd2 Bd2");
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    play(&mut lm, "e5 Qg4 e4 Qxg7");
+    assert_eq!(lm.status(), GameStatus::VariantWin(Color::White));
+    assert_eq!(Pgn::from_game(&lm).result, "1-0");
+
+    //the state of the variant is restored by undo
+    lm.undo();
+    assert_eq!(lm.status(), GameStatus::Ongoing);
+    assert_eq!(lm.variant().as_any().downcast_ref::<Kamikaze>().unwrap().captures, [1, 1]);
+    lm.redo();
+    assert_eq!(lm.status(), GameStatus::VariantWin(Color::White));
+}
+
+#[test]
+fn unknown_variants_are_rejected() {
+    let pgn = Pgn::parse("[Variant \"Kamikaze\"]\n\n1. e4 *").unwrap();
+    assert_eq!(pgn.replay().err(), Some(PgnError::UnknownVariant("Kamikaze".to_string())));
+    let pgn = Pgn::parse("[Variant \"Standard\"]\n\n1. e4 *").unwrap();
+    assert!(pgn.replay().is_ok());
+}
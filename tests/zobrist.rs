@@ -0,0 +1,66 @@
+use chess::logic::LogicManager;
+use chess::logic::piece::PieceTypes;
+
+//walks the game tree and checks that the incrementally updated hash always matches the hash of the same position loaded from FEN
+fn check_hashes(lm: &LogicManager, depth: u32) {
+    let from_fen = LogicManager::from_fen(&lm.to_fen()).unwrap();
+    assert_eq!(lm.position_hash(), from_fen.position_hash(), "hash of {}", lm.to_fen());
+    if depth == 0 {
+        return;
+    }
+    for i in 0..8 {
+        for j in 0..8 {
+            if lm.get_piece_color((i, j)) != Some(lm.side_to_move()) {
+                continue;
+            }
+            let mut selected = lm.clone();
+            let destinations = selected.get_possible_moves((i, j)).cloned().unwrap_or_default();
+            let is_pawn = lm.get_board()[i as usize][j as usize].as_ref().unwrap().piece_type() == PieceTypes::Pawn;
+            for to in destinations {
+                let mut next = selected.clone();
+                if is_pawn && (to.0 == 0 || to.0 == 7) {
+                    next.promote_pawn(to, PieceTypes::Knight);
+                } else {
+                    next.move_piece(to);
+                }
+                check_hashes(&next, depth - 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn incremental_hash_matches_fen() {
+    check_hashes(&LogicManager::new(), 2);
+    //castling, en passant and promotions
+    check_hashes(&LogicManager::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap(), 2);
+    check_hashes(&LogicManager::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap(), 2);
+    check_hashes(&LogicManager::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap(), 3);
+}
+
+#[test]
+fn hash_depends_on_side_to_move_and_en_passant() {
+    let white = LogicManager::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let black = LogicManager::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(white.position_hash(), black.position_hash());
+
+    //the en passant square only matters when the capture is possible
+    let capturable = LogicManager::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let not_capturable = LogicManager::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(capturable.position_hash(), not_capturable.position_hash());
+    let no_pawn = LogicManager::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+    let no_pawn_plain = LogicManager::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(no_pawn.position_hash(), no_pawn_plain.position_hash());
+}
+
+#[test]
+fn undo_restores_hash() {
+    let mut lm = LogicManager::new();
+    let start = lm.position_hash();
+    let mv = lm.parse_san("e4").unwrap();
+    lm.get_possible_moves(mv.from.pos());
+    lm.move_piece(mv.to.pos());
+    assert_ne!(lm.position_hash(), start);
+    lm.undo();
+    assert_eq!(lm.position_hash(), start);
+}